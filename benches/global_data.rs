@@ -0,0 +1,160 @@
+//! Measures the cost of looking up and reading global data with each approach
+//!
+//! Run with `cargo bench`. The results are printed to stdout as CSV, with one row per case:
+//!
+//! ```text
+//! group,case,threads,ns_per_op
+//! keyword_lookup,phf,1,4.21
+//! ```
+//!
+//! `ns_per_op` is the median over several samples. For the `read` group it's the time one thread
+//! takes for one read while the other threads are reading too, from the slowest thread. Spawning
+//! the threads isn't timed. Any arguments that don't start with `-` filter the cases by
+//! substring, e.g. `cargo bench -- arc_swap`.
+
+use std::collections::HashMap;
+use std::env;
+use std::hint::black_box;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use global_data_in_rust::keywords::{
+    Keyword2, KEYWORDS, KEYWORDS_MATCH, KEYWORDS_SORTED, LIVE_KEYWORDS,
+};
+use lazy_static::lazy_static;
+use parking_lot::{Mutex, RwLock};
+
+const SAMPLES: usize = 7;
+const LOOKUPS: usize = 200_000;
+const READS: usize = 100_000;
+const THREADS: &[usize] = &[1, 2, 4, 8, 16, 32, 64];
+
+// Half keywords, half near misses, as a tokenizer would see them
+const WORDS: &[&str] = &[
+    "loop", "x", "continue", "loops", "break", "fun", "fn", "external", "extern", "count",
+];
+
+lazy_static! {
+    static ref HASH_MAP: HashMap<&'static str, Keyword2> =
+        KEYWORDS.entries().map(|(k, v)| (*k, *v)).collect();
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    max_players: u32,
+    tick_rate: u32,
+}
+
+const CONFIG: Config = Config {
+    max_players: 8,
+    tick_rate: 60,
+};
+
+struct Bench {
+    filters: Vec<String>,
+}
+
+impl Bench {
+    /// Prints the median time per operation of `run`, which does `ops` operations per call and
+    /// returns how long they took
+    fn case(
+        &self,
+        group: &str,
+        case: &str,
+        threads: usize,
+        ops: usize,
+        mut run: impl FnMut() -> Duration,
+    ) {
+        let name = format!("{}/{}", group, case);
+        if !self.filters.is_empty() && !self.filters.iter().any(|f| name.contains(f.as_str())) {
+            return;
+        }
+        run();
+        let mut samples: Vec<f64> = (0..SAMPLES)
+            .map(|_| run().as_nanos() as f64 / ops as f64)
+            .collect();
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        println!("{},{},{},{:.2}", group, case, threads, samples[SAMPLES / 2]);
+    }
+
+    fn lookup(&self, case: &str, get: impl Fn(&str) -> Option<Keyword2>) {
+        for word in KEYWORDS.keys().chain(WORDS) {
+            assert_eq!(
+                get(word),
+                KEYWORDS.get(*word).copied(),
+                "{}: {}",
+                case,
+                word
+            );
+        }
+        self.case("keyword_lookup", case, 1, LOOKUPS * WORDS.len(), || {
+            let start = Instant::now();
+            for _ in 0..LOOKUPS {
+                for word in WORDS {
+                    black_box(get(black_box(word)));
+                }
+            }
+            start.elapsed()
+        });
+    }
+
+    /// `threads` threads each call `read` `READS` times, starting together. Each thread times its
+    /// own reads, and the slowest one counts.
+    fn read(&self, case: &str, threads: usize, read: impl Fn() -> u32 + Sync) {
+        let barrier = Barrier::new(threads);
+        self.case("read", case, threads, READS, || {
+            thread::scope(|scope| {
+                let readers: Vec<_> = (0..threads)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            barrier.wait();
+                            let start = Instant::now();
+                            for _ in 0..READS {
+                                black_box(read());
+                            }
+                            start.elapsed()
+                        })
+                    })
+                    .collect();
+                readers
+                    .into_iter()
+                    .map(|reader| reader.join().unwrap())
+                    .max()
+                    .unwrap()
+            })
+        });
+    }
+}
+
+fn main() {
+    let bench = Bench {
+        filters: env::args()
+            .skip(1)
+            .filter(|a| !a.starts_with('-'))
+            .collect(),
+    };
+    println!("group,case,threads,ns_per_op");
+
+    bench.lookup("phf", |word| KEYWORDS.get(word).copied());
+    bench.lookup("lazy_static_hash_map", |word| HASH_MAP.get(word).copied());
+    bench.lookup("match", |word| KEYWORDS_MATCH.get(word).copied());
+    bench.lookup("binary_search", |word| KEYWORDS_SORTED.get(word).copied());
+    bench.lookup("runtime_phf_arc_swap", |word| {
+        LIVE_KEYWORDS.load().get(word).copied()
+    });
+
+    let arc_swap = ArcSwap::from_pointee(CONFIG);
+    let rw_lock = RwLock::new(CONFIG);
+    let mutex = Mutex::new(CONFIG);
+    for &threads in THREADS {
+        bench.read("arc_swap", threads, || arc_swap.load().max_players);
+        bench.read("arc_swap_full", threads, || {
+            let config: Arc<Config> = arc_swap.load_full();
+            config.tick_rate
+        });
+        bench.read("rw_lock", threads, || rw_lock.read().max_players);
+        bench.read("mutex", threads, || mutex.lock().max_players);
+    }
+}
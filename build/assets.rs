@@ -0,0 +1,115 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::integrity::sha256_literal;
+use crate::{fail, out_file};
+
+// Collects the files and directories under `dir`, with paths relative to the root of the tree
+fn walk(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<String>,
+    dirs: &mut Vec<(String, Vec<(String, bool)>)>,
+) {
+    let display = dir.display().to_string();
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .unwrap_or_else(|e| fail(&display, 0, &e.to_string()))
+        .map(|entry| {
+            entry
+                .unwrap_or_else(|e| fail(&display, 0, &e.to_string()))
+                .path()
+        })
+        .collect();
+    entries.sort();
+
+    let relative = |path: &Path| {
+        let path = path.strip_prefix(root).unwrap();
+        let parts: Vec<_> = path
+            .iter()
+            .map(|part| part.to_str().expect("asset paths have to be UTF-8"))
+            .collect();
+        parts.join("/")
+    };
+
+    let mut listing = Vec::new();
+    for path in &entries {
+        let is_dir = path.is_dir();
+        listing.push((relative(path), is_dir));
+        if !is_dir {
+            files.push(relative(path));
+        }
+    }
+    dirs.push((relative(dir), listing));
+
+    for path in entries.iter().filter(|path| path.is_dir()) {
+        walk(root, path, files, dirs);
+    }
+}
+
+// Embeds every file under `dir` and generates the `ASSETS` static in `src/assets.rs`
+pub fn build(dir: &str) {
+    println!("cargo:rerun-if-changed={}", dir);
+
+    let root = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(dir);
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    walk(&root, &root, &mut files, &mut dirs);
+    files.sort();
+
+    let mut file_values = Vec::new();
+    for path in &files {
+        let full_path = root.join(path);
+        let contents = fs::read(&full_path).unwrap_or_else(|e| fail(path, 0, &e.to_string()));
+        file_values.push(format!(
+            "File {{ contents: include_bytes!({:?}), size: {}, sha256: {} }}",
+            full_path.to_str().unwrap(),
+            contents.len(),
+            sha256_literal(&contents)
+        ));
+    }
+    let mut file_map = phf_codegen::Map::new();
+    for (path, value) in files.iter().zip(&file_values) {
+        file_map.entry(path.as_str(), value);
+    }
+
+    let dir_values: Vec<String> = dirs
+        .iter()
+        .map(|(_, listing)| {
+            let entries: Vec<String> = listing
+                .iter()
+                .map(|(path, is_dir)| format!("Entry {{ path: {:?}, is_dir: {} }}", path, is_dir))
+                .collect();
+            format!("&[{}]", entries.join(", "))
+        })
+        .collect();
+    let mut dir_map = phf_codegen::Map::new();
+    for ((path, _), value) in dirs.iter().zip(&dir_values) {
+        dir_map.entry(path.as_str(), value);
+    }
+
+    // Only `dev-assets` reads from disk, and otherwise the build machine's path would end up in
+    // the binary
+    let root = if env::var_os("CARGO_FEATURE_DEV_ASSETS").is_some() {
+        format!("\n    root: {:?},", root.to_str().unwrap())
+    } else {
+        String::new()
+    };
+    let mut out = out_file("assets.rs");
+    writeln!(
+        out,
+        "/// Every file under `{dir}/`, keyed by its path relative to `{dir}/`
+pub static ASSETS: Assets = Assets {{{root}
+    files: &{files},
+    dirs: &{dirs},
+    paths: &{paths:?},
+}};",
+        dir = dir,
+        root = root,
+        files = file_map.build(),
+        dirs = dir_map.build(),
+        paths = files,
+    )
+    .unwrap();
+}
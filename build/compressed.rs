@@ -0,0 +1,71 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::integrity::Blob;
+
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+
+use crate::validate::content_hash;
+use crate::{fail, out_file};
+
+// Compresses each `(path, static name)` asset into `OUT_DIR` and generates a `Compressed` static
+// for it. Returns the compressed files, which are what actually gets embedded.
+pub fn build(assets: &[(&str, &str)]) -> Vec<Blob> {
+    let mut file = out_file("compressed.rs");
+    let mut blobs = Vec::new();
+    for &(path, static_name) in assets {
+        println!("cargo:rerun-if-changed={}", path);
+
+        let original = fs::read(path).unwrap_or_else(|e| fail(path, 0, &e.to_string()));
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&original).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let name = format!("{}.deflate", static_name.to_ascii_lowercase());
+        let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join(&name);
+        fs::write(&out_path, &compressed).unwrap();
+        println!(
+            "cargo:warning=compressed {}: {} bytes -> {} bytes",
+            path,
+            original.len(),
+            compressed.len()
+        );
+
+        writeln!(
+            file,
+            "/// `{path}`, compressed by the build script from {original} to {compressed} bytes
+pub static {static_name}: Compressed = Compressed::new(
+    include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{name}\")),
+    {original},
+    {hash:#018x},
+);
+",
+            path = path,
+            static_name = static_name,
+            name = name,
+            original = original.len(),
+            compressed = compressed.len(),
+            hash = content_hash(&original),
+        ).unwrap();
+
+        blobs.push(Blob {
+            name: format!("{} (compressed)", path),
+            type_name: format!("{}Deflate", upper_camel_case(static_name)),
+            path: out_path,
+            bytes: format!("include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{}\"))", name),
+        });
+    }
+    blobs
+}
+
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let word = word.to_ascii_lowercase();
+            word[..1].to_ascii_uppercase() + &word[1..]
+        })
+        .collect()
+}
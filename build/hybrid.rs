@@ -0,0 +1,33 @@
+use std::fs;
+use std::io::Write;
+
+use crate::difficulty;
+use crate::validate::{content_hash, DataError};
+use crate::{fail, fail_with, out_file};
+
+// Validates a data file that `src/hybrid.rs` loads at run time, and returns a line of generated
+// code that declares the hash of its contents
+fn check<T>(path: &str, const_name: &str, parse: fn(&str) -> Result<T, DataError>) -> String {
+    println!("cargo:rerun-if-changed={}", path);
+
+    let text = fs::read_to_string(path).unwrap_or_else(|e| fail(path, 0, &e.to_string()));
+    if let Err(e) = parse(&text) {
+        fail_with(path, e);
+    }
+    format!(
+        "/// The hash of `{}` when the build script validated it\n\
+         pub const {}: u64 = {:#018x};\n",
+        path,
+        const_name,
+        content_hash(text.as_bytes())
+    )
+}
+
+pub fn build() {
+    let mut file = out_file("hybrid.rs");
+    write!(
+        file,
+        "{}",
+        check("data/difficulties.csv", "DIFFICULTIES_HASH", difficulty::parse)
+    ).unwrap();
+}
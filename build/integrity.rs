@@ -0,0 +1,68 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+
+use crate::{fail, out_file};
+
+// An embedded blob that `src/integrity.rs` checks at run time
+pub struct Blob {
+    pub name: String,
+    // The unit struct that gets a `Blob` impl with the blob's consts
+    pub type_name: String,
+    // The file whose contents are embedded
+    pub path: PathBuf,
+    // A const expression for the embedded bytes, so the check covers the bytes that are used
+    pub bytes: String,
+}
+
+// The SHA-256 of `bytes`, as a Rust array literal
+pub fn sha256_literal(bytes: &[u8]) -> String {
+    let bytes: Vec<String> = Sha256::digest(bytes).iter().map(|b| format!("{:#04x}", b)).collect();
+    format!("[{}]", bytes.join(", "))
+}
+
+pub fn build(blobs: &[Blob]) {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let mut file = out_file("integrity.rs");
+    for blob in blobs {
+        let display = blob.path.display().to_string();
+        // Compressed blobs are written to `OUT_DIR` by this build script, and
+        // `compressed::build` already watches their sources
+        if !blob.path.starts_with(&out_dir) {
+            println!("cargo:rerun-if-changed={}", display);
+        }
+        let contents = fs::read(&blob.path).unwrap_or_else(|e| fail(&display, 0, &e.to_string()));
+        writeln!(
+            file,
+            "/// `{name}`, as it was when the crate was built
+pub struct {type_name};
+
+impl Blob for {type_name} {{
+    const NAME: &'static str = {name:?};
+    const BYTES: &'static [u8] = {bytes};
+    const LEN: usize = {len};
+    const SHA256: [u8; 32] = {sha256};
+}}
+",
+            name = blob.name,
+            type_name = blob.type_name,
+            bytes = blob.bytes,
+            len = contents.len(),
+            sha256 = sha256_literal(&contents),
+        ).unwrap();
+    }
+
+    let refs: Vec<String> = blobs
+        .iter()
+        .map(|blob| format!("{}::blob_ref()", blob.type_name))
+        .collect();
+    writeln!(
+        file,
+        "/// Every blob with a `Blob` impl\n\
+         fn generated_blobs() -> Vec<BlobRef> {{\n    vec![{}]\n}}",
+        refs.join(", ")
+    ).unwrap();
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+
+use crate::data_files;
+use crate::lookup::{self, Lookup};
+use crate::{fail, fail_with, out_file};
+
+const KEYWORDS_PATH: &str = "data/keywords.csv";
+
+// The build-time half of `keywords::get_normalized`, which applies the same steps to its input
+fn normalize(key: &str) -> String {
+    let key = key.trim();
+    #[cfg(feature = "nfc")]
+    let key: String = unicode_normalization::UnicodeNormalization::nfc(key).collect();
+    key.to_ascii_lowercase()
+}
+
+// Loads the keyword table from `data/keywords.csv` and generates both the `Keyword2` enum and
+// the `KEYWORDS` map from it, so that the two can't drift apart. Returns the variants, in order,
+// so that tables can be keyed by them.
+pub fn build(lookup: Lookup) -> Vec<String> {
+    println!("cargo:rerun-if-changed={}", KEYWORDS_PATH);
+
+    let text = fs::read_to_string(KEYWORDS_PATH)
+        .unwrap_or_else(|e| fail(KEYWORDS_PATH, 0, &e.to_string()));
+    let rows = data_files::read_keywords(&text).unwrap_or_else(|e| fail_with(KEYWORDS_PATH, e));
+    let mut entries = Vec::new();
+    let mut normalized_lines: HashMap<String, (u64, String)> = HashMap::new();
+    let mut normalized_entries = Vec::new();
+    // Several keywords may map to the same variant, so this keeps the first occurrence of each.
+    // That first keyword is the variant's canonical spelling, used by `as_str` and `Display`.
+    let mut variants: Vec<(String, String)> = Vec::new();
+
+    for row in &rows {
+        let (line, keyword, variant) = (row.line, &row.keyword, &row.variant);
+        if !variants.iter().any(|(v, _)| v == variant) {
+            variants.push((variant.to_string(), keyword.to_string()));
+        }
+        let normalized = normalize(keyword);
+        match normalized_lines.get(&normalized) {
+            // Spelling variants of the same keyword are fine as long as they agree
+            Some((_, first_variant)) if first_variant == variant => {}
+            Some((first, _)) => fail(
+                KEYWORDS_PATH,
+                line,
+                &format!(
+                    "keyword `{}` normalizes to `{}`, which line {} maps to a different variant",
+                    keyword, normalized, first
+                ),
+            ),
+            None => {
+                normalized_lines.insert(normalized.clone(), (line, variant.to_string()));
+                normalized_entries.push((normalized, format!("Keyword2::{}", variant)));
+            }
+        }
+
+        entries.push((keyword.to_string(), format!("Keyword2::{}", variant)));
+    }
+
+    let mut normalized_map = phf_codegen::Map::new();
+    for (keyword, value) in &normalized_entries {
+        normalized_map.entry(keyword.as_str(), value);
+    }
+    let max_normalized_len = normalized_entries.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+
+    let mut file = out_file("keywords.rs");
+
+    writeln!(&mut file, "/// A keyword, as listed in `data/keywords.csv`").unwrap();
+    writeln!(&mut file, "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]").unwrap();
+    writeln!(&mut file, "pub enum Keyword2 {{").unwrap();
+    for (variant, _) in &variants {
+        writeln!(&mut file, "    {},", variant).unwrap();
+    }
+    writeln!(&mut file, "}}\n").unwrap();
+
+    writeln!(&mut file, "impl Keyword2 {{").unwrap();
+    writeln!(&mut file, "    /// Every variant, in the order they first appear in the data file").unwrap();
+    writeln!(&mut file, "    pub const ALL: &[Keyword2] = &[").unwrap();
+    for (variant, _) in &variants {
+        writeln!(&mut file, "        Keyword2::{},", variant).unwrap();
+    }
+    writeln!(&mut file, "    ];\n").unwrap();
+    writeln!(&mut file, "    /// The number of variants").unwrap();
+    writeln!(&mut file, "    pub const fn count() -> usize {{").unwrap();
+    writeln!(&mut file, "        {}", variants.len()).unwrap();
+    writeln!(&mut file, "    }}\n").unwrap();
+    writeln!(&mut file, "    /// The canonical text of this keyword").unwrap();
+    writeln!(&mut file, "    pub fn as_str(self) -> &'static str {{").unwrap();
+    writeln!(&mut file, "        match self {{").unwrap();
+    for (variant, keyword) in &variants {
+        writeln!(&mut file, "            Keyword2::{} => {:?},", variant, keyword).unwrap();
+    }
+    writeln!(&mut file, "        }}").unwrap();
+    writeln!(&mut file, "    }}").unwrap();
+    writeln!(&mut file).unwrap();
+    writeln!(&mut file, "    /// The variant with this name, e.g. `Loop` for `Keyword2::Loop`").unwrap();
+    writeln!(&mut file, "    pub fn from_variant_name(name: &str) -> Option<Keyword2> {{").unwrap();
+    writeln!(&mut file, "        match name {{").unwrap();
+    for (variant, _) in &variants {
+        writeln!(&mut file, "            {:?} => Some(Keyword2::{}),", variant, variant).unwrap();
+    }
+    writeln!(&mut file, "            _ => None,").unwrap();
+    writeln!(&mut file, "        }}").unwrap();
+    writeln!(&mut file, "    }}").unwrap();
+    writeln!(&mut file, "}}\n").unwrap();
+
+    writeln!(
+        &mut file,
+        "impl std::fmt::Display for Keyword2 {{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+        f.write_str(self.as_str())
+    }}
+}}
+
+impl crate::lookup::Key for Keyword2 {{
+    const BITS: u32 = {bits};
+
+    fn to_u64(self) -> u64 {{
+        self as u64
+    }}
+}}
+
+impl std::str::FromStr for Keyword2 {{
+    type Err = ParseKeywordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {{
+        KEYWORDS.get(s).copied().ok_or(ParseKeywordError(()))
+    }}
+}}
+",
+        bits = lookup::enum_bits(variants.len())
+    ).unwrap();
+
+    writeln!(&mut file, "/// Maps the text of each keyword to its `Keyword2`").unwrap();
+    write!(&mut file, "pub static KEYWORDS: ").unwrap();
+    lookup.generate(&mut file, "Keyword2", &entries);
+    writeln!(&mut file, ";\n").unwrap();
+
+    // The same table in the other representations, so that `cargo bench` can compare them
+    for (name, other) in &[("KEYWORDS_MATCH", Lookup::Match), ("KEYWORDS_SORTED", Lookup::Sorted)] {
+        writeln!(&mut file, "#[doc(hidden)]").unwrap();
+        write!(&mut file, "pub static {}: ", name).unwrap();
+        other.generate(&mut file, "Keyword2", &entries);
+        writeln!(&mut file, ";\n").unwrap();
+    }
+
+    writeln!(
+        &mut file,
+        "/// Like `KEYWORDS`, but keyed by normalized text. Use `get_normalized` to look things up.\n\
+         static KEYWORDS_NORMALIZED: phf::Map<&'static str, Keyword2> = \n{};\n",
+        normalized_map.build()
+    ).unwrap();
+    writeln!(
+        &mut file,
+        "const MAX_NORMALIZED_LEN: usize = {};",
+        max_normalized_len
+    ).unwrap();
+
+    variants.into_iter().map(|(variant, _)| variant).collect()
+}
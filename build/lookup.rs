@@ -0,0 +1,121 @@
+use std::io::Write;
+
+// How a generated map from strings to values is represented. They all have the same `get` API
+// (see `src/lookup.rs`), so each table can use whichever `cargo bench` says is best for it.
+#[derive(Clone, Copy)]
+pub enum Lookup {
+    // A `phf::Map`: a perfect hash, which stays fast as the table grows
+    Phf,
+    // A `MatchMap`: a `match` on the key, which the compiler can turn into a few comparisons
+    Match,
+    // A `SortedMap`: a binary search of a sorted slice, which is the most compact
+    Sorted,
+    // An `OrderedMap`: a perfect hash into a slice, so that iteration follows the file
+    Ordered,
+}
+
+impl Lookup {
+    // Writes the type and initializer of a static that maps each key to its value. The values
+    // are Rust expressions of type `value_type`.
+    pub fn generate(self, file: &mut impl Write, value_type: &str, entries: &[(String, String)]) {
+        match self {
+            Lookup::Phf => {
+                let mut map = phf_codegen::Map::new();
+                for (key, value) in entries {
+                    map.entry(key.as_str(), value);
+                }
+                write!(
+                    file,
+                    "phf::Map<&'static str, {}> = \n{}",
+                    value_type,
+                    map.build()
+                ).unwrap();
+            }
+            Lookup::Match => {
+                writeln!(
+                    file,
+                    "crate::lookup::MatchMap<{}> = crate::lookup::MatchMap::new(",
+                    value_type
+                ).unwrap();
+                write_entries(file, entries.iter());
+                writeln!(file, "    |key| match key {{").unwrap();
+                for (i, (key, _)) in entries.iter().enumerate() {
+                    writeln!(file, "        {:?} => Some({}),", key, i).unwrap();
+                }
+                writeln!(file, "        _ => None,").unwrap();
+                writeln!(file, "    }},").unwrap();
+                write!(file, ")").unwrap();
+            }
+            Lookup::Sorted => {
+                let mut sorted: Vec<&(String, String)> = entries.iter().collect();
+                sorted.sort_by(|a, b| a.0.cmp(&b.0));
+                writeln!(
+                    file,
+                    "crate::lookup::SortedMap<{}> = crate::lookup::SortedMap::new(",
+                    value_type
+                ).unwrap();
+                write_entries(file, sorted.into_iter());
+                write!(file, ")").unwrap();
+            }
+            Lookup::Ordered => {
+                let mut index = phf_codegen::Map::new();
+                let positions: Vec<String> = (0..entries.len()).map(|i| i.to_string()).collect();
+                for ((key, _), position) in entries.iter().zip(&positions) {
+                    index.entry(key.as_str(), position);
+                }
+                writeln!(
+                    file,
+                    "crate::lookup::OrderedMap<{}> = crate::lookup::OrderedMap::new(",
+                    value_type
+                ).unwrap();
+                write_entries(file, entries.iter());
+                write!(file, "{},\n)", index.build()).unwrap();
+            }
+        }
+    }
+}
+
+// A key that isn't a string, packed into a `u64` the same way as `Key::to_u64` does at run time
+pub struct PackedKey {
+    pub packed: u64,
+    // The key as a Rust expression
+    pub literal: String,
+}
+
+// Writes the type and initializer of a `KeyMap`. The values are Rust expressions.
+pub fn key_map(
+    file: &mut impl Write,
+    key_type: &str,
+    value_type: &str,
+    entries: &[(PackedKey, String)],
+) {
+    let mut index = phf_codegen::Map::new();
+    let positions: Vec<String> = (0..entries.len()).map(|i| i.to_string()).collect();
+    for ((key, _), position) in entries.iter().zip(&positions) {
+        index.entry(key.packed, position);
+    }
+    writeln!(
+        file,
+        "crate::lookup::KeyMap<{}, {}> = crate::lookup::KeyMap::new(",
+        key_type, value_type
+    ).unwrap();
+    writeln!(file, "    &[").unwrap();
+    for (key, value) in entries {
+        writeln!(file, "        ({}, {}),", key.literal, value).unwrap();
+    }
+    writeln!(file, "    ],").unwrap();
+    write!(file, "{},\n)", index.build()).unwrap();
+}
+
+// The number of bits that `Key::to_u64` uses for an enum with this many variants
+pub fn enum_bits(variants: usize) -> u32 {
+    (64 - (variants as u64).saturating_sub(1).leading_zeros()).max(1)
+}
+
+fn write_entries<'a>(file: &mut impl Write, entries: impl Iterator<Item = &'a (String, String)>) {
+    writeln!(file, "    &[").unwrap();
+    for (key, value) in entries {
+        writeln!(file, "        ({:?}, {}),", key, value).unwrap();
+    }
+    writeln!(file, "    ],").unwrap();
+}
@@ -0,0 +1,187 @@
+extern crate csv;
+extern crate flate2;
+extern crate phf_codegen;
+extern crate sha2;
+extern crate skeptic;
+#[cfg(feature = "nfc")]
+extern crate unicode_normalization;
+
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::process;
+
+// These are shared with the library, which uses the parts that the build script doesn't
+#[allow(dead_code)]
+#[path = "../src/data_files.rs"]
+mod data_files;
+#[allow(dead_code)]
+#[path = "../src/difficulty.rs"]
+mod difficulty;
+#[allow(dead_code)]
+#[path = "../src/validate.rs"]
+mod validate;
+
+mod assets;
+mod compressed;
+mod hybrid;
+mod integrity;
+mod keywords;
+mod lookup;
+mod tables;
+
+use data_files::{Column, Enums, Type};
+use lookup::Lookup;
+use tables::{Set, Table};
+
+// Reports a problem in a data file and stops the build
+pub fn fail(path: &str, line: u64, message: &str) -> ! {
+    eprintln!("error: {}:{}: {}", path, line, message);
+    process::exit(1);
+}
+
+// Like `fail`, but for a problem with a specific field
+pub fn fail_at(path: &str, line: u64, column: usize, message: &str) -> ! {
+    eprintln!("error: {}:{}:{}: {}", path, line, column, message);
+    process::exit(1);
+}
+
+// Reports an error from checking a data file and stops the build
+pub fn fail_with(path: &str, error: validate::DataError) -> ! {
+    match error.column {
+        Some(column) => fail_at(path, error.line, column, &error.message),
+        None => fail(path, error.line, &error.message),
+    }
+}
+
+// Creates a file in `OUT_DIR` for generated code
+pub fn out_file(name: &str) -> BufWriter<File> {
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join(name);
+    BufWriter::new(File::create(&path).unwrap())
+}
+
+// The typed tables in `src/tables.rs`
+const TABLES: &[Table] = &[
+    Table {
+        path: "data/ammo.csv",
+        struct_name: "Ammo",
+        static_name: "AMMO",
+        key: &["name"],
+        lookup: Lookup::Sorted,
+        columns: &[
+            Column { name: "name", ty: Type::Str },
+            Column { name: "damage_bonus", ty: Type::U16 },
+            Column { name: "weight", ty: Type::F32 },
+        ],
+    },
+    Table {
+        path: "data/weapons.csv",
+        struct_name: "Weapon",
+        static_name: "WEAPONS",
+        key: &["name"],
+        lookup: Lookup::Phf,
+        columns: &[
+            Column { name: "name", ty: Type::Str },
+            Column { name: "damage", ty: Type::U16 },
+            Column { name: "range", ty: Type::F32 },
+            Column { name: "weight", ty: Type::F32 },
+            Column { name: "two_handed", ty: Type::Bool },
+            Column { name: "ammo", ty: Type::OptionalRef("Ammo") },
+        ],
+    },
+    Table {
+        path: "data/levels.csv",
+        struct_name: "Level",
+        static_name: "LEVELS",
+        key: &["level"],
+        lookup: Lookup::Phf,
+        columns: &[
+            Column { name: "level", ty: Type::U8 },
+            Column { name: "experience", ty: Type::U32 },
+            Column { name: "title", ty: Type::Str },
+        ],
+    },
+    Table {
+        path: "data/zones.csv",
+        struct_name: "Zone",
+        static_name: "ZONES",
+        key: &["name"],
+        lookup: Lookup::Ordered,
+        columns: &[
+            Column { name: "name", ty: Type::Str },
+            Column { name: "id", ty: Type::U16 },
+            Column { name: "min_level", ty: Type::U8 },
+        ],
+    },
+    Table {
+        path: "data/loot.csv",
+        struct_name: "Loot",
+        static_name: "LOOT",
+        key: &["zone", "tier"],
+        lookup: Lookup::Phf,
+        columns: &[
+            Column { name: "zone", ty: Type::U16 },
+            Column { name: "tier", ty: Type::U8 },
+            Column { name: "weapon", ty: Type::Ref("Weapon") },
+            Column { name: "chance", ty: Type::F32 },
+        ],
+    },
+    Table {
+        path: "data/keyword_docs.csv",
+        struct_name: "KeywordDoc",
+        static_name: "KEYWORD_DOCS",
+        key: &["keyword"],
+        lookup: Lookup::Phf,
+        columns: &[
+            Column { name: "keyword", ty: Type::Enum(KEYWORD) },
+            Column { name: "summary", ty: Type::Str },
+        ],
+    },
+];
+
+// The sets in `src/tables.rs`
+const SETS: &[Set] = &[
+    Set {
+        path: "data/reserved_keywords.csv",
+        static_name: "RESERVED_KEYWORDS",
+        column: Column { name: "keyword", ty: Type::Str },
+    },
+    Set {
+        path: "data/zones.csv",
+        static_name: "ZONE_IDS",
+        column: Column { name: "id", ty: Type::U16 },
+    },
+];
+
+// The enum generated by `build/keywords.rs`, which tables can use as a column type
+const KEYWORD: &str = "crate::keywords::Keyword2";
+
+// Assets that `src/compressed.rs` embeds compressed, as (path, static name)
+const COMPRESSED: &[(&str, &str)] = &[("sample.txt", "SAMPLE")];
+
+// Blobs that `src/integrity.rs` checks, as (name, type name, expression for the embedded bytes).
+// Compressed assets are added automatically.
+const BLOBS: &[(&str, &str, &str)] = &[("sample.txt", "SampleTxt", "crate::SAMPLE_BYTES")];
+
+fn main() {
+    // generates doc tests for `README.md`.
+    skeptic::generate_doc_tests(&["README.md"]);
+
+    let variants = keywords::build(Lookup::Phf);
+    let enums: Enums = vec![(KEYWORD, variants)].into_iter().collect();
+    tables::build(TABLES, SETS, &enums);
+    hybrid::build();
+    let mut blobs: Vec<integrity::Blob> = BLOBS
+        .iter()
+        .map(|&(name, type_name, bytes)| integrity::Blob {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            path: name.into(),
+            bytes: bytes.to_string(),
+        })
+        .collect();
+    blobs.extend(compressed::build(COMPRESSED));
+    integrity::build(&blobs);
+    assets::build("assets");
+}
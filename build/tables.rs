@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+
+use crate::data_files::{variants, Column, Enums, Ids, Rows, Type};
+use crate::lookup::{self, Lookup, PackedKey};
+use crate::{fail, fail_at, fail_with, out_file};
+
+impl Type {
+    // How many bits `Key::to_u64` packs a key of this type into, if it can be part of a `KeyMap` key
+    fn key_bits(self, enums: &Enums) -> Option<u32> {
+        match self {
+            Type::U8 => Some(8),
+            Type::U16 => Some(16),
+            Type::U32 | Type::I32 => Some(32),
+            Type::U64 | Type::I64 => Some(64),
+            Type::Enum(path) => Some(lookup::enum_bits(variants(enums, path).len())),
+            _ => None,
+        }
+    }
+}
+
+// A CSV file in `data/` that gets turned into a static array of structs, plus an index
+pub struct Table {
+    pub path: &'static str,
+    pub struct_name: &'static str,
+    pub static_name: &'static str,
+    // The columns to index by, whose values have to be unique. Either a single `Type::Str`
+    // column, or one or two columns of integer or enum types, which are indexed by a `KeyMap`.
+    pub key: &'static [&'static str],
+    // How the index by `key` is represented. `KeyMap`s are always perfect hashes in file order.
+    pub lookup: Lookup,
+    pub columns: &'static [Column],
+}
+
+// A column of a data file that gets turned into a `phf::Set` of its values
+pub struct Set {
+    pub path: &'static str,
+    pub static_name: &'static str,
+    // A `Type::Str` or integer column, whose values have to be unique
+    pub column: Column,
+}
+
+// A table whose data file has been read, but whose fields haven't been converted to Rust yet
+struct Loaded<'a> {
+    table: &'a Table,
+    data: Rows,
+    // The key of each row packed into a `u64`, unless the table is keyed by a string
+    packed_keys: Option<Vec<u64>>,
+}
+
+impl Table {
+    // Reads the data file and checks that it has the declared columns and unique keys
+    fn load<'a>(&'a self, enums: &Enums) -> Loaded<'a> {
+        println!("cargo:rerun-if-changed={}", self.path);
+
+        let text = fs::read_to_string(self.path).unwrap_or_else(|e| fail(self.path, 0, &e.to_string()));
+        let data = Rows::read(&text, self.columns).unwrap_or_else(|e| fail_with(self.path, e));
+        let packed_keys = self.pack_keys(&data, enums);
+        data.check_keys(self.columns, &self.key_indices(), packed_keys.as_deref())
+            .unwrap_or_else(|e| fail_with(self.path, e));
+        Loaded {
+            table: self,
+            data,
+            packed_keys,
+        }
+    }
+
+    fn key_indices(&self) -> Vec<usize> {
+        self.key
+            .iter()
+            .map(|key| {
+                self.columns
+                    .iter()
+                    .position(|column| column.name == *key)
+                    .unwrap_or_else(|| panic!("{}: key column `{}` isn't declared", self.path, key))
+            })
+            .collect()
+    }
+
+    // Packs each row's key like `Key::to_u64`, or returns `None` if the table is keyed by a string
+    fn pack_keys(&self, data: &Rows, enums: &Enums) -> Option<Vec<u64>> {
+        let indices = self.key_indices();
+        if let [i] = indices[..] {
+            if self.columns[i].ty == Type::Str {
+                return None;
+            }
+        }
+        if !matches!(self.lookup, Lookup::Phf | Lookup::Ordered) {
+            panic!("{}: only string keys can be looked up with a `match` or a binary search", self.path);
+        }
+        let bits: Vec<u32> = indices
+            .iter()
+            .map(|&i| {
+                let column = &self.columns[i];
+                column.ty.key_bits(enums).unwrap_or_else(|| {
+                    panic!("{}: key column `{}` has to be an integer or an enum", self.path, column.name)
+                })
+            })
+            .collect();
+        if indices.len() > 2 || bits.iter().sum::<u32>() > 64 {
+            panic!("{}: a key can have at most two columns, which fit in 64 bits", self.path);
+        }
+
+        let packed = data
+            .pack_keys(self.columns, &indices, &bits, enums)
+            .unwrap_or_else(|e| fail_with(self.path, e));
+        Some(packed)
+    }
+}
+
+impl<'a> Loaded<'a> {
+    fn keys(&self) -> HashMap<String, usize> {
+        let key = self.table.key_indices();
+        self.data
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| (Rows::key_text(row, &key), i))
+            .collect()
+    }
+
+    fn generate(&self, file: &mut impl Write, ids: &Ids, enums: &Enums) {
+        let table = self.table;
+        let rows = self
+            .data
+            .literals(table.columns, ids, enums)
+            .unwrap_or_else(|e| fail_with(table.path, e));
+        let key_indices = table.key_indices();
+        let id_name = format!("{}Id", table.struct_name);
+        let index_name = format!(
+            "{}_BY_{}",
+            table.static_name,
+            table.key.join("_").to_ascii_uppercase()
+        );
+
+        writeln!(file, "/// A row of `{}`", table.path).unwrap();
+        writeln!(file, "#[derive(Clone, Copy, Debug, PartialEq)]").unwrap();
+        writeln!(file, "pub struct {} {{", table.struct_name).unwrap();
+        for column in table.columns {
+            writeln!(file, "    pub {}: {},", column.name, column.ty.rust_type()).unwrap();
+        }
+        writeln!(file, "}}\n").unwrap();
+
+        writeln!(file, "/// Every row of `{}`, in file order", table.path).unwrap();
+        writeln!(
+            file,
+            "pub static {}: [{}; {}] = [",
+            table.static_name,
+            table.struct_name,
+            rows.len()
+        ).unwrap();
+        for row in &rows {
+            writeln!(file, "    {} {{", table.struct_name).unwrap();
+            for (column, value) in table.columns.iter().zip(row) {
+                writeln!(file, "        {}: {},", column.name, value).unwrap();
+            }
+            writeln!(file, "    }},").unwrap();
+        }
+        writeln!(file, "];\n").unwrap();
+
+        let key_names: Vec<String> = table.key.iter().map(|key| format!("`{}`", key)).collect();
+        writeln!(
+            file,
+            "/// Maps each {} to its index in `{}`",
+            key_names.join(" and "),
+            table.static_name
+        ).unwrap();
+        write!(file, "pub static {}: ", index_name).unwrap();
+        match &self.packed_keys {
+            None => {
+                let entries: Vec<(String, String)> = self
+                    .data
+                    .rows
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| (Rows::key_text(row, &key_indices), i.to_string()))
+                    .collect();
+                table.lookup.generate(file, "usize", &entries);
+            }
+            Some(packed_keys) => {
+                let entries: Vec<(PackedKey, String)> = rows
+                    .iter()
+                    .zip(packed_keys)
+                    .enumerate()
+                    .map(|(i, (row, &packed))| {
+                        let literal = tuple(key_indices.iter().map(|&k| row[k].clone()));
+                        (PackedKey { packed, literal }, i.to_string())
+                    })
+                    .collect();
+                let key_type = tuple(key_indices.iter().map(|&k| table.columns[k].ty.rust_type()));
+                lookup::key_map(file, &key_type, "usize", &entries);
+            }
+        }
+        writeln!(file, ";\n").unwrap();
+
+        // The parameters of `id` and `get`, and the key to look up made out of them
+        let params: Vec<String> = key_indices
+            .iter()
+            .map(|&k| match table.columns[k].ty {
+                Type::Str => format!("{}: &str", table.columns[k].name),
+                ty => format!("{}: {}", table.columns[k].name, ty.rust_type()),
+            })
+            .collect();
+        let key = tuple(table.key.iter().map(|key| key.to_string()));
+
+        writeln!(
+            file,
+            "/// The index of a row in `{static_name}`. Ids are only created by the generated code, so
+/// they always refer to a row that exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct {id}(u16);
+
+impl {id} {{
+    /// The row that this id refers to
+    pub fn get(self) -> &'static {struct_name} {{
+        &{static_name}[self.0 as usize]
+    }}
+
+    /// The index of the row in `{static_name}`
+    pub fn index(self) -> usize {{
+        self.0 as usize
+    }}
+}}
+
+impl {struct_name} {{
+    /// Looks up the id of a row by its {key_names}
+    pub fn id({params}) -> Option<{id}> {{
+        {index_name}.get({key}).map(|&i| {id}(i as u16))
+    }}
+
+    /// Looks up a row by its {key_names}
+    pub fn get({params}) -> Option<&'static {struct_name}> {{
+        Self::id({args}).map({id}::get)
+    }}
+}}
+",
+            static_name = table.static_name,
+            struct_name = table.struct_name,
+            id = id_name,
+            key_names = key_names.join(" and "),
+            params = params.join(", "),
+            key = key,
+            args = table.key.join(", "),
+            index_name = index_name,
+        ).unwrap();
+    }
+}
+
+impl Set {
+    fn generate(&self, file: &mut impl Write, enums: &Enums) {
+        println!("cargo:rerun-if-changed={}", self.path);
+        let Column { name, ty } = self.column;
+
+        let mut reader = csv::Reader::from_path(self.path)
+            .unwrap_or_else(|e| fail(self.path, 0, &e.to_string()));
+        let position = reader
+            .headers()
+            .unwrap_or_else(|e| fail(self.path, 1, &e.to_string()))
+            .iter()
+            .position(|header| header == name)
+            .unwrap_or_else(|| fail(self.path, 1, &format!("missing column `{}`", name)));
+
+        let mut values = Vec::new();
+        // Compared as literals, so that `7` and `07` count as duplicates
+        let mut lines: HashMap<String, u64> = HashMap::new();
+        for record in reader.records() {
+            let record = record.unwrap_or_else(|e| {
+                let line = e.position().map_or(0, |p| p.line());
+                fail(self.path, line, &e.to_string())
+            });
+            let line = record.position().map_or(0, |p| p.line());
+            let field = &record[position];
+            let literal = ty.literal(field, &Ids::new(), enums).unwrap_or_else(|message| {
+                fail_at(self.path, line, position + 1, &format!("column `{}`: {}", name, message))
+            });
+            if let Some(first) = lines.insert(literal, line) {
+                fail(
+                    self.path,
+                    line,
+                    &format!("duplicate {} `{}` (first defined on line {})", name, field, first),
+                );
+            }
+            values.push(field.to_string());
+        }
+
+        // `phf_codegen::Set` hashes the values themselves, so each type needs its own set
+        macro_rules! build_set {
+            ($($ty:ident => $rust:ty),*) => {
+                match ty {
+                    Type::Str => {
+                        let mut set = phf_codegen::Set::new();
+                        for value in &values {
+                            set.entry(value.as_str());
+                        }
+                        set.build().to_string()
+                    }
+                    $(Type::$ty => {
+                        let mut set = phf_codegen::Set::new();
+                        for value in &values {
+                            set.entry(value.parse::<$rust>().unwrap());
+                        }
+                        set.build().to_string()
+                    })*
+                    _ => panic!("{}: a set can only contain strings and integers", self.path),
+                }
+            };
+        }
+        let set = build_set!(U8 => u8, U16 => u16, U32 => u32, U64 => u64, I32 => i32, I64 => i64);
+
+        writeln!(file, "/// Every `{}` in `{}`", name, self.path).unwrap();
+        writeln!(
+            file,
+            "pub static {}: phf::Set<{}> = \n{};\n",
+            self.static_name,
+            ty.rust_type(),
+            set
+        ).unwrap();
+    }
+}
+
+// A Rust tuple of `items`, or the item itself if there's only one
+fn tuple(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.collect();
+    match &items[..] {
+        [item] => item.clone(),
+        _ => format!("({})", items.join(", ")),
+    }
+}
+
+pub fn build(tables: &[Table], sets: &[Set], enums: &Enums) {
+    // Every table is loaded before any code is generated, so that references can point anywhere
+    let loaded: Vec<Loaded> = tables.iter().map(|table| table.load(enums)).collect();
+    let ids: Ids = loaded
+        .iter()
+        .map(|loaded| (loaded.table.struct_name, loaded.keys()))
+        .collect();
+
+    let mut file = out_file("tables.rs");
+    for loaded in &loaded {
+        loaded.generate(&mut file, &ids, enums);
+    }
+    for set in sets {
+        set.generate(&mut file, enums);
+    }
+}
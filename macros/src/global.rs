@@ -0,0 +1,165 @@
+// `global!`: declare statics by their properties and let the macro pick the primitive
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Error, Ident, ItemStatic, Token};
+
+pub struct Input {
+    items: Vec<ItemStatic>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(input.parse()?);
+        }
+        Ok(Input { items })
+    }
+}
+
+const PROPERTIES: &str = "`const`, `runtime`, `mutable`, `reloadable` or `no_heap`";
+
+// The answers to the questions in the README's "Tradeoffs" section
+#[derive(Default)]
+struct Properties {
+    constant: Option<Ident>,
+    runtime: Option<Ident>,
+    mutable: Option<Ident>,
+    reloadable: Option<Ident>,
+    no_heap: Option<Ident>,
+}
+
+impl Properties {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Properties> {
+        let mut properties = Properties::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("global")) {
+            let idents = attr.parse_args_with(|input: ParseStream| {
+                Punctuated::<Ident, Token![,]>::parse_terminated_with(input, Ident::parse_any)
+            })?;
+            for ident in idents {
+                let slot = match ident.to_string().as_str() {
+                    "const" => &mut properties.constant,
+                    "runtime" => &mut properties.runtime,
+                    "mutable" => &mut properties.mutable,
+                    "reloadable" => &mut properties.reloadable,
+                    "no_heap" => &mut properties.no_heap,
+                    other => {
+                        return Err(Error::new(
+                            ident.span(),
+                            format!("unknown property `{}`, expected {}", other, PROPERTIES),
+                        ))
+                    }
+                };
+                if slot.is_some() {
+                    return Err(Error::new(
+                        ident.span(),
+                        format!("`{}` is given more than once", ident),
+                    ));
+                }
+                *slot = Some(ident);
+            }
+        }
+        properties.check()?;
+        Ok(properties)
+    }
+
+    fn check(&self) -> syn::Result<()> {
+        if let (Some(_), Some(runtime)) = (&self.constant, &self.runtime) {
+            return Err(Error::new(
+                runtime.span(),
+                "a global can't be both `const` and `runtime`",
+            ));
+        }
+        if let (Some(_), Some(mutable)) = (&self.constant, &self.mutable) {
+            return Err(Error::new(
+                mutable.span(),
+                "a global can't be both `const` and `mutable`",
+            ));
+        }
+        if let Some(reloadable) = &self.reloadable {
+            if self.runtime.is_none() {
+                return Err(Error::new(
+                    reloadable.span(),
+                    "`reloadable` globals are loaded at run time, so they need `runtime` too",
+                ));
+            }
+            if self.mutable.is_some() {
+                return Err(Error::new(
+                    reloadable.span(),
+                    "`reloadable` globals can already be replaced with `try_set`, \
+                     so they can't also be `mutable`",
+                ));
+            }
+            if self.no_heap.is_some() {
+                return Err(Error::new(
+                    reloadable.span(),
+                    "`reloadable` globals are kept in an `Arc`, which needs the heap",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn expand(input: Input) -> syn::Result<TokenStream> {
+    let mut output = TokenStream::new();
+    for item in input.items {
+        output.extend(expand_item(item)?);
+    }
+    Ok(output)
+}
+
+fn expand_item(item: ItemStatic) -> syn::Result<TokenStream> {
+    if let Some(mutability) = &item.mutability {
+        return Err(Error::new_spanned(
+            mutability,
+            "use `#[global(mutable)]` instead of `static mut`",
+        ));
+    }
+    let properties = Properties::parse(&item.attrs)?;
+    let attrs = item
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path.is_ident("global"));
+    let ItemStatic {
+        vis,
+        ident,
+        ty,
+        expr,
+        ..
+    } = &item;
+
+    // These paths assume that the crate is called `global_data_in_rust`, as proc macros have no `$crate`
+    let global = quote!(::global_data_in_rust::global);
+    let (ty, expr) = match (
+        properties.runtime.is_some(),
+        properties.mutable.is_some(),
+        properties.reloadable.is_some(),
+    ) {
+        (false, false, _) => (quote!(#global::Const<#ty>), quote!(#global::Const(#expr))),
+        (false, true, _) => (
+            quote!(#global::__private::Mutex<#ty>),
+            quote!(#global::__private::const_mutex(#expr)),
+        ),
+        (true, false, false) => (
+            quote!(#global::__private::Lazy<#ty>),
+            quote!(#global::__private::Lazy::new(|| #expr)),
+        ),
+        (true, true, _) => (
+            quote!(#global::LazyMutex<#ty>),
+            quote!(#global::LazyMutex::new(|| #expr)),
+        ),
+        (true, false, true) => (
+            quote!(#global::Reloadable<#ty>),
+            quote!(#global::Reloadable::new(|| ::std::result::Result::Ok(#expr))),
+        ),
+    };
+    Ok(quote! {
+        #(#attrs)*
+        #vis static #ident: #ty = #expr;
+    })
+}
@@ -0,0 +1,288 @@
+//! Procedural macros for `global-data-in-rust`. See `include_data!` and `global!`.
+
+extern crate proc_macro;
+
+mod global;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, parse_macro_input, Ident, LitStr, Token};
+
+/// Parses a JSON or TOML file at compile time and expands to a literal of a user type, so it can
+/// initialize a `const` or a `static`.
+///
+/// ```ignore
+/// static CONFIG: Config = include_data!("data/config.toml" as Config {
+///     server: Server,
+///     users: [User],
+/// });
+/// ```
+///
+/// The path is relative to the crate's `Cargo.toml`. Tables become struct literals, arrays become
+/// `&[...]` slices, and strings become `&'static str`s. A file that is an array of tables can be
+/// included `as [T]`. The macro can't see the definition of
+/// `Config`, so the type of every nested table has to be given after the path, using `[T]` for an
+/// array of tables. The same goes for floats that the file may write as integers, like
+/// `tick_rate = 60`: list them as `tick_rate: f32`, or `[f32]` for an array.
+///
+/// Syntax errors in the file are reported with their line and column. Any other mismatch between
+/// the file and the types is caught by the compiler, and the error points at the path.
+#[proc_macro]
+pub fn include_data(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as Input);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(message) => {
+            let message = format!("{}: {}", input.path.value(), message);
+            quote_spanned!(input.path.span()=> compile_error!(#message)).into()
+        }
+    }
+}
+
+/// Declares statics by the properties they need, and picks the matching primitive.
+///
+/// ```ignore
+/// global! {
+///     #[global(runtime, reloadable)]
+///     pub static CONFIG: Config = Config::load();
+/// }
+/// ```
+///
+/// | Properties            | Expands to                                                   |
+/// |-----------------------|--------------------------------------------------------------|
+/// | none, or `const`      | `Const<T>`                                                   |
+/// | `mutable`             | `parking_lot::Mutex<T>`                                      |
+/// | `runtime`             | `once_cell::sync::Lazy<T>`                                   |
+/// | `runtime, mutable`    | `LazyMutex<T>`                                               |
+/// | `runtime, reloadable` | `Reloadable<T>`, an `ArcSwap` that can rerun the initializer |
+///
+/// Without `runtime`, the initializer has to be a constant expression. `const` also promises that
+/// the value never changes, so it rules out `runtime` and `mutable`. `no_heap` doesn't change the
+/// expansion, but rules out `reloadable`. All of the expansions implement `GlobalData`.
+#[proc_macro]
+pub fn global(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as global::Input);
+    global::expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+struct Input {
+    path: LitStr,
+    ty: FieldSpec,
+}
+
+// The type of a table, along with the types of any tables nested inside it
+struct TypeSpec {
+    path: syn::Path,
+    fields: BTreeMap<String, FieldSpec>,
+}
+
+enum FieldSpec {
+    Table(TypeSpec),
+    ArrayOf(TypeSpec),
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![as]>()?;
+        let ty = input.parse()?;
+        Ok(Input { path, ty })
+    }
+}
+
+impl Parse for TypeSpec {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut fields = BTreeMap::new();
+        if input.peek(syn::token::Brace) {
+            let content;
+            braced!(content in input);
+            while !content.is_empty() {
+                let name: Ident = content.parse()?;
+                content.parse::<Token![:]>()?;
+                fields.insert(name.to_string(), content.parse()?);
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+        }
+        Ok(TypeSpec { path, fields })
+    }
+}
+
+impl Parse for FieldSpec {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Bracket) {
+            let element;
+            bracketed!(element in input);
+            Ok(FieldSpec::ArrayOf(element.parse()?))
+        } else {
+            Ok(FieldSpec::Table(input.parse()?))
+        }
+    }
+}
+
+impl TypeSpec {
+    fn is_float(&self) -> bool {
+        self.fields.is_empty() && (self.path.is_ident("f32") || self.path.is_ident("f64"))
+    }
+}
+
+impl FieldSpec {
+    fn table(&self) -> &TypeSpec {
+        match self {
+            FieldSpec::Table(spec) | FieldSpec::ArrayOf(spec) => spec,
+        }
+    }
+}
+
+// A format-independent view of the parsed file
+enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Vec<(String, Value)>),
+}
+
+impl From<toml::Value> for Value {
+    fn from(value: toml::Value) -> Value {
+        match value {
+            toml::Value::String(s) => Value::String(s),
+            toml::Value::Integer(i) => Value::Integer(i),
+            toml::Value::Float(f) => Value::Float(f),
+            toml::Value::Boolean(b) => Value::Boolean(b),
+            toml::Value::Datetime(d) => Value::String(d.to_string()),
+            toml::Value::Array(a) => Value::Array(a.into_iter().map(Value::from).collect()),
+            toml::Value::Table(t) => {
+                Value::Table(t.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+        }
+    }
+}
+
+impl Value {
+    fn from_json(value: serde_json::Value, key: &str) -> Result<Value, String> {
+        Ok(match value {
+            serde_json::Value::Null => {
+                return Err(format!("`{}` is null, which isn't supported", key))
+            }
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Bool(b) => Value::Boolean(b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => Value::Float(n.as_f64().unwrap()),
+            },
+            serde_json::Value::Array(a) => Value::Array(
+                a.into_iter()
+                    .map(|v| Value::from_json(v, key))
+                    .collect::<Result<_, _>>()?,
+            ),
+            serde_json::Value::Object(o) => Value::Table(
+                o.into_iter()
+                    .map(|(k, v)| Ok((k.clone(), Value::from_json(v, &k)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+        })
+    }
+
+    // `ty` is the type of this value if it's a table, or of its elements if it's an array
+    fn to_tokens(
+        &self,
+        key: &str,
+        ty: Option<&TypeSpec>,
+        span: Span,
+    ) -> Result<TokenStream, String> {
+        let mut literal = match self {
+            Value::String(s) => Literal::string(s),
+            // JSON has no separate integers, and TOML files often leave out the `.0`
+            Value::Integer(i) if ty.is_some_and(TypeSpec::is_float) => {
+                Literal::f64_unsuffixed(*i as f64)
+            }
+            Value::Integer(i) => Literal::i64_unsuffixed(*i),
+            Value::Float(f) if f.is_finite() => Literal::f64_unsuffixed(*f),
+            Value::Float(f) => return Err(format!("`{}` is {}, which isn't supported", key, f)),
+            Value::Boolean(b) => {
+                return Ok(if *b {
+                    quote_spanned!(span=> true)
+                } else {
+                    quote_spanned!(span=> false)
+                })
+            }
+            Value::Array(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| value.to_tokens(key, ty, span))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(quote_spanned!(span=> &[#(#values),*]));
+            }
+            Value::Table(entries) => {
+                let ty = ty.ok_or_else(|| {
+                    format!(
+                        "`{}` is or contains a table, so its type has to be given, \
+                         e.g. `{}: MyType` or `{}: [MyType]`",
+                        key, key, key
+                    )
+                })?;
+                let path = &ty.path;
+                let fields = entries
+                    .iter()
+                    .map(|(name, value)| {
+                        let field_ty = ty.fields.get(name).map(FieldSpec::table);
+                        let mut ident = syn::parse_str::<Ident>(name)
+                            .map_err(|_| format!("`{}` is not a valid field name", name))?;
+                        ident.set_span(span);
+                        let value = value.to_tokens(name, field_ty, span)?;
+                        Ok(quote_spanned!(span=> #ident: #value))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                return Ok(quote_spanned!(span=> #path { #(#fields),* }));
+            }
+        };
+        literal.set_span(span);
+        Ok(quote!(#literal))
+    }
+}
+
+// Both parsers include the line and column in their error messages
+fn parse_file(path: &Path, text: &str) -> Result<Value, String> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => text
+            .parse::<toml::Value>()
+            .map(Value::from)
+            .map_err(|e| e.to_string()),
+        Some("json") => {
+            let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+            Value::from_json(value, "the file")
+        }
+        _ => Err("only .toml and .json files are supported".to_string()),
+    }
+}
+
+fn expand(input: &Input) -> Result<TokenStream, String> {
+    // Without Cargo, e.g. when `skeptic` runs `rustc` directly, paths are relative to the current directory
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    let path = Path::new(&root).join(input.path.value());
+    let text = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let value = parse_file(&path, &text)?;
+    let span = input.path.span();
+    let literal = value.to_tokens("the file", Some(input.ty.table()), span)?;
+
+    // `include_bytes!` makes the compiler rebuild the crate when the file changes
+    let path = path.to_str().ok_or("the path isn't valid UTF-8")?;
+    Ok(quote_spanned! {span=>
+        {
+            const _: &[u8] = include_bytes!(#path);
+            #literal
+        }
+    })
+}
@@ -0,0 +1,146 @@
+//! A whole directory tree embedded in the binary
+//!
+//! The build script embeds every file under `assets/` with `include_bytes!` and indexes them
+//! with phf maps. With the `dev-assets` feature, file contents and metadata are read from disk
+//! instead, so edits show up without a rebuild. Adding or removing files still needs a rebuild,
+//! since the listing is generated by the build script either way.
+
+use std::borrow::Cow;
+
+use sha2::{Digest, Sha256};
+
+include!(concat!(env!("OUT_DIR"), "/assets.rs"));
+
+/// An embedded directory tree. Paths are relative to its root and separated by `/`, and the root
+/// itself is `""`.
+pub struct Assets {
+    /// Where the tree was when it was embedded. Only kept with `dev-assets`, so that the build
+    /// machine's paths don't end up in release binaries.
+    #[cfg(feature = "dev-assets")]
+    root: &'static str,
+    files: &'static phf::Map<&'static str, File>,
+    dirs: &'static phf::Map<&'static str, &'static [Entry]>,
+    /// Every file path, sorted
+    paths: &'static [&'static str],
+}
+
+pub(crate) struct File {
+    pub(crate) contents: &'static [u8],
+    pub(crate) size: usize,
+    pub(crate) sha256: [u8; 32],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub path: &'static str,
+    pub is_dir: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    pub size: usize,
+    pub sha256: [u8; 32],
+}
+
+impl Assets {
+    /// The contents of the file at `path`. This is borrowed from the binary unless the
+    /// `dev-assets` feature is on.
+    pub fn get(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+        let file = self.files.get(path)?;
+        if cfg!(feature = "dev-assets") {
+            self.read_from_disk(path).map(Cow::Owned)
+        } else {
+            Some(Cow::Borrowed(file.contents))
+        }
+    }
+
+    #[cfg(feature = "dev-assets")]
+    fn read_from_disk(&self, path: &str) -> Option<Vec<u8>> {
+        std::fs::read(std::path::Path::new(self.root).join(path)).ok()
+    }
+
+    #[cfg(not(feature = "dev-assets"))]
+    fn read_from_disk(&self, _path: &str) -> Option<Vec<u8>> {
+        unreachable!("files are only read from disk with `dev-assets`")
+    }
+
+    pub fn metadata(&self, path: &str) -> Option<Metadata> {
+        let file = self.files.get(path)?;
+        if cfg!(feature = "dev-assets") {
+            let contents = self.get(path)?;
+            Some(Metadata {
+                size: contents.len(),
+                sha256: Sha256::digest(&contents).into(),
+            })
+        } else {
+            Some(Metadata {
+                size: file.size,
+                sha256: file.sha256,
+            })
+        }
+    }
+
+    /// The files and directories directly inside the directory at `path`, sorted by path
+    pub fn read_dir(&self, path: &str) -> Option<&'static [Entry]> {
+        self.dirs.get(path.trim_end_matches('/')).copied()
+    }
+
+    /// Every file as it's embedded in the binary, even with `dev-assets`
+    pub(crate) fn embedded(&self) -> impl Iterator<Item = (&'static str, &'static File)> + '_ {
+        self.paths
+            .iter()
+            .map(move |&path| (path, self.files.get(path).unwrap()))
+    }
+
+    /// Every file path, sorted
+    pub fn paths(&self) -> impl Iterator<Item = &'static str> {
+        self.paths.iter().copied()
+    }
+
+    /// The paths of the files that match `pattern`, sorted. In the pattern, `*` matches anything
+    /// but `/`, `?` matches one character other than `/`, and `**` matches any number of
+    /// directories.
+    pub fn glob<'a>(&self, pattern: &'a str) -> impl Iterator<Item = &'static str> + 'a {
+        let pattern: Vec<&str> = pattern.split('/').collect();
+        self.paths().filter(move |path| {
+            let path: Vec<&str> = path.split('/').collect();
+            matches_segments(&pattern, &path)
+        })
+    }
+}
+
+fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            // Either `**` matches nothing, or it swallows one more directory
+            matches_segments(&pattern[1..], path)
+                || (!path.is_empty() && matches_segments(pattern, &path[1..]))
+        }
+        (Some(p), Some(s)) => {
+            matches_segment(p.as_bytes(), s.as_bytes())
+                && matches_segments(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn matches_segment(pattern: &[u8], segment: &[u8]) -> bool {
+    match (pattern.first(), segment.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches_segment(&pattern[1..], segment)
+                || (!segment.is_empty() && matches_segment(pattern, &segment[1..]))
+        }
+        // `?` has to match a whole character, not just one byte of it
+        (Some(b'?'), Some(_)) => {
+            let len = std::str::from_utf8(segment)
+                .ok()
+                .and_then(|s| s.chars().next())
+                .map_or(1, char::len_utf8);
+            matches_segment(&pattern[1..], &segment[len..])
+        }
+        (Some(p), Some(s)) => p == s && matches_segment(&pattern[1..], &segment[1..]),
+        _ => false,
+    }
+}
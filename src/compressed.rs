@@ -0,0 +1,87 @@
+//! Files that are compressed at build time and decompressed the first time they're used
+
+use std::fmt;
+use std::io::Read;
+use std::ops::Deref;
+
+use flate2::read::DeflateDecoder;
+use once_cell::sync::OnceCell;
+
+use crate::validate::content_hash;
+
+include!(concat!(env!("OUT_DIR"), "/compressed.rs"));
+
+/// Embedded data that is stored compressed in the binary. It's decompressed on first access,
+/// which allocates the decompressed size on the heap, and kept for the rest of the program.
+pub struct Compressed {
+    compressed: &'static [u8],
+    len: usize,
+    hash: u64,
+    cell: OnceCell<Box<[u8]>>,
+}
+
+/// The decompressed data didn't match what the build script compressed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorruptError;
+
+impl fmt::Display for CorruptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("compressed data is corrupt")
+    }
+}
+
+impl std::error::Error for CorruptError {}
+
+impl Compressed {
+    /// `len` and `hash` are the length and `content_hash` of the original data
+    pub const fn new(compressed: &'static [u8], len: usize, hash: u64) -> Self {
+        Compressed {
+            compressed,
+            len,
+            hash,
+            cell: OnceCell::new(),
+        }
+    }
+
+    /// The decompressed data, decompressing it first if this is the first access
+    pub fn try_get(&self) -> Result<&[u8], CorruptError> {
+        let data = self.cell.get_or_try_init(|| {
+            let mut data = Vec::with_capacity(self.len);
+            DeflateDecoder::new(self.compressed)
+                .read_to_end(&mut data)
+                .map_err(|_| CorruptError)?;
+            if data.len() != self.len || content_hash(&data) != self.hash {
+                return Err(CorruptError);
+            }
+            Ok(data.into_boxed_slice())
+        })?;
+        Ok(data)
+    }
+
+    /// Like `try_get`, but panics if the data is corrupt
+    pub fn get(&self) -> &[u8] {
+        self.try_get().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The length of the decompressed data. This doesn't need to decompress anything.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of bytes that the data takes up in the binary
+    pub fn compressed_len(&self) -> usize {
+        self.compressed.len()
+    }
+}
+
+impl Deref for Compressed {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.get()
+    }
+}
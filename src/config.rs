@@ -0,0 +1,237 @@
+//! Configuration that is reloaded from a file while the program runs
+//!
+//! A `ReloadableConfig` reads its file once when it's opened. After that, `reload` reads it again
+//! on demand, and `watch` starts a thread that polls the file for changes. Every new version is
+//! parsed and validated before it's published with `ArcSwap::store`, so readers only ever see a
+//! value that passed validation. When a new version doesn't, the previous value stays in place
+//! and the error is reported.
+//!
+//! Polling doesn't depend on any OS-specific file notification API. Each poll reads the whole
+//! file and compares its `content_hash` with the last version, which is cheap for a config file
+//! and doesn't depend on the resolution of file timestamps. If a poll catches the file half
+//! written, the error is reported and the complete file is picked up on the next poll.
+//!
+//! To find out when a new value is published, use the `Subscribe` trait. To undo one, use
+//! `rollback`.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use arc_swap::Guard;
+use parking_lot::Mutex;
+
+use crate::history::{RollbackError, Version, Versions, DEFAULT_HISTORY};
+use crate::subscribe::{Subscribe, Subscribers};
+use crate::validate::content_hash;
+
+pub type ParseFn<T> = fn(&str) -> Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Checks a parsed value, returning a description of the problem if it isn't valid
+pub type ValidateFn<T> = fn(&T) -> Result<(), String>;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, Box<dyn Error + Send + Sync>),
+    Invalid(PathBuf, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ConfigError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+            ConfigError::Invalid(path, message) => {
+                write!(f, "{}: invalid config: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(_, e) => Some(e),
+            ConfigError::Parse(_, e) => Some(&**e),
+            ConfigError::Invalid(..) => None,
+        }
+    }
+}
+
+/// A value loaded from a file, which is replaced when the file changes
+pub struct ReloadableConfig<T> {
+    path: PathBuf,
+    parse: ParseFn<T>,
+    validate: ValidateFn<T>,
+    current: Versions<T>,
+    // The `content_hash` of the last version of the file that was read, whether or not it was
+    // valid, so that the watcher doesn't report the same error on every poll. Reloads hold the
+    // lock, so that they publish in the order that they read the file.
+    last_hash: Mutex<u64>,
+    subscribers: Subscribers<T>,
+}
+
+impl<T> ReloadableConfig<T> {
+    /// Reads, parses and validates the file. There's no previous value to fall back on yet, so
+    /// any error is returned.
+    pub fn open(
+        path: impl Into<PathBuf>,
+        parse: ParseFn<T>,
+        validate: ValidateFn<T>,
+    ) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let text = fs::read_to_string(&path).map_err(|e| ConfigError::Io(path.clone(), e))?;
+        let value = check(&path, parse, validate, &text)?;
+        Ok(ReloadableConfig {
+            current: Versions::new(
+                Arc::new(value),
+                format!("opened {}", path.display()),
+                DEFAULT_HISTORY,
+            ),
+            last_hash: Mutex::new(content_hash(text.as_bytes())),
+            path,
+            parse,
+            validate,
+            subscribers: Subscribers::new(),
+        })
+    }
+
+    /// Keeps this many past versions instead of `DEFAULT_HISTORY`
+    pub fn keep_history(mut self, versions: usize) -> Self {
+        self.current.set_capacity(versions);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The current version, which derefs to the value. Like `ArcSwap::load`, this doesn't take
+    /// a lock.
+    pub fn load(&self) -> Guard<'static, Arc<Version<T>>> {
+        self.current.load()
+    }
+
+    /// The current value, which stays alive for as long as you hold on to it
+    pub fn load_full(&self) -> Arc<T> {
+        self.current.load().value.clone()
+    }
+
+    /// The current version followed by the past ones, newest first
+    pub fn history(&self) -> Vec<Arc<Version<T>>> {
+        self.current.history()
+    }
+
+    /// Publishes the value from `n` versions ago again, as a new version. It stays in place until
+    /// the file changes or is reloaded.
+    pub fn rollback(&self, n: usize) -> Result<(), RollbackError> {
+        self.subscribers.check_not_notifying();
+        // Holding the lock keeps a reload from being published in between
+        let _last_hash = self.last_hash.lock();
+        let (old, new) = self.current.rollback(n)?;
+        self.subscribers.notify(&old.value, &new.value);
+        Ok(())
+    }
+
+    /// Reads the file again and publishes it if it's valid, even if it hasn't changed
+    pub fn reload(&self) -> Result<(), ConfigError> {
+        self.reload_if(|_| true, "reloaded").map(|_| ())
+    }
+
+    /// Reads the file and publishes it if it has changed since the last read and it's valid.
+    /// Returns whether a new value was published.
+    pub fn poll(&self) -> Result<bool, ConfigError> {
+        self.reload_if(|changed| changed, "noticed a change to")
+    }
+
+    fn reload_if(
+        &self,
+        should_reload: impl FnOnce(bool) -> bool,
+        source: &str,
+    ) -> Result<bool, ConfigError> {
+        self.subscribers.check_not_notifying();
+        let mut last_hash = self.last_hash.lock();
+        let text =
+            fs::read_to_string(&self.path).map_err(|e| ConfigError::Io(self.path.clone(), e))?;
+        let hash = content_hash(text.as_bytes());
+        if !should_reload(hash != *last_hash) {
+            return Ok(false);
+        }
+        *last_hash = hash;
+        let value = Arc::new(check(&self.path, self.parse, self.validate, &text)?);
+        let source = format!("{} {}", source, self.path.display());
+        let (old, new) = self.current.publish(value, source);
+        self.subscribers.notify(&old.value, &new.value);
+        Ok(true)
+    }
+}
+
+/// Subscribers hear about every value that's published, whether from `reload`, `poll` or the
+/// watcher. Reloads happen one at a time, so they hear about them in order.
+impl<T: 'static> Subscribe for ReloadableConfig<T> {
+    type Value = T;
+
+    fn subscribers(&self) -> &Subscribers<T> {
+        &self.subscribers
+    }
+}
+
+impl<T: Send + Sync + 'static> ReloadableConfig<T> {
+    /// Starts a thread that calls `poll` every `interval` and passes any errors to `on_error`.
+    /// The thread stops when the returned `Watcher` is dropped.
+    pub fn watch(
+        &'static self,
+        interval: Duration,
+        mut on_error: impl FnMut(ConfigError) + Send + 'static,
+    ) -> Watcher {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            // Dropping the sender wakes this up immediately, rather than after the next interval
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                if let Err(e) = self.poll() {
+                    on_error(e);
+                }
+            }
+        });
+        Watcher {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+/// Parses and validates one version of the file
+fn check<T>(
+    path: &Path,
+    parse: ParseFn<T>,
+    validate: ValidateFn<T>,
+    text: &str,
+) -> Result<T, ConfigError> {
+    let value = parse(text).map_err(|e| ConfigError::Parse(path.to_owned(), e))?;
+    validate(&value).map_err(|message| ConfigError::Invalid(path.to_owned(), message))?;
+    Ok(value)
+}
+
+/// The thread started by `ReloadableConfig::watch`
+pub struct Watcher {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for Watcher {
+    /// Stops the thread and waits for any poll in progress to finish
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            // A panic in `on_error` has already been reported by the thread
+            let _ = thread.join();
+        }
+    }
+}
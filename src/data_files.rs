@@ -0,0 +1,361 @@
+//! Parsing and checking of the CSV files in `data/` that the build script turns into code
+//!
+//! The build script includes this file with `#[path]`, like `validate`, and stops the build at
+//! the first `DataError`. The library exposes it so that those errors can be tested.
+
+use std::collections::HashMap;
+
+use crate::validate::DataError;
+
+/// The types that a table column can have
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Type {
+    Str,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I32,
+    I64,
+    F32,
+    F64,
+    /// The key of a row in the table with this struct name, stored as that table's id type
+    Ref(&'static str),
+    /// Like `Ref`, but an empty field means `None`
+    OptionalRef(&'static str),
+    /// A variant of the enum at this path, written as the variant's name
+    Enum(&'static str),
+}
+
+/// Each table's keys and their row indices, keyed by struct name, for resolving references
+pub type Ids<'a> = HashMap<&'a str, HashMap<String, usize>>;
+
+/// The variants of each generated enum that tables can use, keyed by path
+pub type Enums = HashMap<&'static str, Vec<String>>;
+
+impl Type {
+    /// The type of the field in the generated struct
+    pub fn rust_type(self) -> String {
+        match self {
+            Type::Str => "&'static str".to_string(),
+            Type::Bool => "bool".to_string(),
+            Type::U8 => "u8".to_string(),
+            Type::U16 => "u16".to_string(),
+            Type::U32 => "u32".to_string(),
+            Type::U64 => "u64".to_string(),
+            Type::I32 => "i32".to_string(),
+            Type::I64 => "i64".to_string(),
+            Type::F32 => "f32".to_string(),
+            Type::F64 => "f64".to_string(),
+            Type::Ref(table) => format!("{}Id", table),
+            Type::OptionalRef(table) => format!("Option<{}Id>", table),
+            Type::Enum(path) => path.to_string(),
+        }
+    }
+
+    /// Parses a key field the same way as `Key::to_u64` packs it at run time
+    pub fn pack(self, field: &str, enums: &Enums) -> Result<u64, String> {
+        let invalid = |_| format!("`{}` is not a valid {}", field, self.rust_type());
+        match self {
+            Type::U8 => field.parse::<u8>().map(u64::from).map_err(invalid),
+            Type::U16 => field.parse::<u16>().map(u64::from).map_err(invalid),
+            Type::U32 => field.parse::<u32>().map(u64::from).map_err(invalid),
+            Type::U64 => field.parse::<u64>().map_err(invalid),
+            Type::I32 => field
+                .parse::<i32>()
+                .map(|i| i as u32 as u64)
+                .map_err(invalid),
+            Type::I64 => field.parse::<i64>().map(|i| i as u64).map_err(invalid),
+            Type::Enum(path) => variants(enums, path)
+                .iter()
+                .position(|variant| variant == field)
+                .map(|i| i as u64)
+                .ok_or_else(|| format!("`{}` is not a variant of `{}`", field, path)),
+            _ => unreachable!("{:?} can't be part of a key", self),
+        }
+    }
+
+    /// Parses a field from a data file and returns it as a Rust literal of this type
+    pub fn literal(self, field: &str, ids: &Ids, enums: &Enums) -> Result<String, String> {
+        fn parse<T: std::str::FromStr + ToString>(field: &str, ty: Type) -> Result<String, String> {
+            field
+                .parse::<T>()
+                .map(|value| value.to_string())
+                .map_err(|_| format!("`{}` is not a valid {}", field, ty.rust_type()))
+        }
+
+        fn parse_float<T: std::str::FromStr + Into<f64> + Copy + std::fmt::Debug>(
+            field: &str,
+            ty: Type,
+        ) -> Result<String, String> {
+            match field.parse::<T>() {
+                Ok(value) if value.into().is_finite() => {
+                    Ok(format!("{:?}{}", value, ty.rust_type()))
+                }
+                _ => Err(format!(
+                    "`{}` is not a valid finite {}",
+                    field,
+                    ty.rust_type()
+                )),
+            }
+        }
+
+        match self {
+            Type::Str => Ok(format!("{:?}", field)),
+            Type::Bool => parse::<bool>(field, self),
+            Type::U8 => parse::<u8>(field, self),
+            Type::U16 => parse::<u16>(field, self),
+            Type::U32 => parse::<u32>(field, self),
+            Type::U64 => parse::<u64>(field, self),
+            Type::I32 => parse::<i32>(field, self),
+            Type::I64 => parse::<i64>(field, self),
+            Type::F32 => parse_float::<f32>(field, self),
+            Type::F64 => parse_float::<f64>(field, self),
+            Type::Ref(table) => {
+                let keys = ids
+                    .get(table)
+                    .unwrap_or_else(|| panic!("there is no table with struct name `{}`", table));
+                match keys.get(field) {
+                    Some(index) => Ok(format!("{}Id({})", table, index)),
+                    None => Err(format!("there is no {} `{}`", table, field)),
+                }
+            }
+            Type::OptionalRef(_) if field.is_empty() => Ok("None".to_string()),
+            Type::OptionalRef(table) => Type::Ref(table)
+                .literal(field, ids, enums)
+                .map(|literal| format!("Some({})", literal)),
+            Type::Enum(path) => self
+                .pack(field, enums)
+                .map(|_| format!("{}::{}", path, field)),
+        }
+    }
+}
+
+/// The variants of the enum at `path`, in order
+pub fn variants<'a>(enums: &'a Enums, path: &str) -> &'a [String] {
+    enums
+        .get(path)
+        .unwrap_or_else(|| panic!("there is no enum at `{}`", path))
+}
+
+pub struct Column {
+    pub name: &'static str,
+    pub ty: Type,
+}
+
+/// A record from a data file, with its fields in the same order as the declared columns
+pub struct Row {
+    pub line: u64,
+    pub fields: Vec<String>,
+}
+
+/// The records of a table's data file, before their fields are converted to Rust
+pub struct Rows {
+    /// Where each declared column is in the file, which doesn't have to be the same order
+    pub positions: Vec<usize>,
+    pub rows: Vec<Row>,
+}
+
+impl Rows {
+    /// Reads a CSV document whose header has exactly the declared `columns`, in any order
+    pub fn read(text: &str, columns: &[Column]) -> Result<Rows, DataError> {
+        let csv_error = |e: csv::Error| DataError {
+            line: e.position().map_or(1, |p| p.line()),
+            column: None,
+            message: e.to_string(),
+        };
+
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers = reader.headers().map_err(csv_error)?.clone();
+        let positions = columns
+            .iter()
+            .map(|column| {
+                headers
+                    .iter()
+                    .position(|header| header == column.name)
+                    .ok_or_else(|| DataError {
+                        line: 1,
+                        column: None,
+                        message: format!("missing column `{}`", column.name),
+                    })
+            })
+            .collect::<Result<Vec<usize>, _>>()?;
+        for (i, header) in headers.iter().enumerate() {
+            if !columns.iter().any(|column| column.name == header) {
+                return Err(DataError {
+                    line: 1,
+                    column: Some(i + 1),
+                    message: format!("undeclared column `{}`", header),
+                });
+            }
+        }
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(csv_error)?;
+            let line = record.position().map_or(0, |p| p.line());
+            // Ids are `u16` indices
+            if rows.len() > u16::MAX as usize {
+                return Err(DataError {
+                    line,
+                    column: None,
+                    message: "too many rows for a u16 id".to_string(),
+                });
+            }
+            let fields = positions.iter().map(|&p| record[p].to_string()).collect();
+            rows.push(Row { line, fields });
+        }
+        Ok(Rows { positions, rows })
+    }
+
+    // An error about the field of `row` in the declared column `index`
+    fn error(&self, row: &Row, columns: &[Column], index: usize, message: String) -> DataError {
+        DataError {
+            line: row.line,
+            column: Some(self.positions[index] + 1),
+            message: format!("column `{}`: {}", columns[index].name, message),
+        }
+    }
+
+    /// The key of a row as written in the data file, with multiple columns separated by commas.
+    /// `key` holds the indices of the key columns.
+    pub fn key_text(row: &Row, key: &[usize]) -> String {
+        let fields: Vec<&str> = key.iter().map(|&i| row.fields[i].as_str()).collect();
+        fields.join(",")
+    }
+
+    /// Packs the key of each row like `Key::to_u64`, with `bits[i]` bits for the column `key[i]`
+    pub fn pack_keys(
+        &self,
+        columns: &[Column],
+        key: &[usize],
+        bits: &[u32],
+        enums: &Enums,
+    ) -> Result<Vec<u64>, DataError> {
+        self.rows
+            .iter()
+            .map(|row| {
+                key.iter().zip(bits).try_fold(0u64, |packed, (&i, &bits)| {
+                    let part = columns[i]
+                        .ty
+                        .pack(&row.fields[i], enums)
+                        .map_err(|message| self.error(row, columns, i, message))?;
+                    // `checked_shl` because shifting a `u64` by 64 would overflow
+                    Ok(packed.checked_shl(bits).unwrap_or(0) | part)
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that no two rows have the same key. Packed keys are compared instead of the text if
+    /// they're given, so that `7` and `07` count as duplicates.
+    pub fn check_keys(
+        &self,
+        columns: &[Column],
+        key: &[usize],
+        packed_keys: Option<&[u64]>,
+    ) -> Result<(), DataError> {
+        let keys: Vec<String> = match packed_keys {
+            Some(packed) => packed.iter().map(u64::to_string).collect(),
+            None => self
+                .rows
+                .iter()
+                .map(|row| Rows::key_text(row, key))
+                .collect(),
+        };
+        let mut lines: HashMap<&str, u64> = HashMap::new();
+        for (row, text) in self.rows.iter().zip(&keys) {
+            if let Some(first) = lines.insert(text, row.line) {
+                let names: Vec<&str> = key.iter().map(|&i| columns[i].name).collect();
+                return Err(DataError {
+                    line: row.line,
+                    column: None,
+                    message: format!(
+                        "duplicate {} `{}` (first defined on line {})",
+                        names.join(" and "),
+                        Rows::key_text(row, key),
+                        first
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts every field to a Rust literal, reporting bad values and dangling references
+    pub fn literals(
+        &self,
+        columns: &[Column],
+        ids: &Ids,
+        enums: &Enums,
+    ) -> Result<Vec<Vec<String>>, DataError> {
+        self.rows
+            .iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .zip(&row.fields)
+                    .enumerate()
+                    .map(|(i, (column, field))| {
+                        column
+                            .ty
+                            .literal(field, ids, enums)
+                            .map_err(|message| self.error(row, columns, i, message))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// A record of `data/keywords.csv`
+pub struct KeywordRow {
+    pub line: u64,
+    pub keyword: String,
+    pub variant: String,
+}
+
+/// Reads the keyword file, which has the columns `keyword,variant`. Each keyword has to be
+/// unique, and each variant has to be a valid type-like identifier, because it ends up verbatim
+/// in the generated code.
+pub fn read_keywords(text: &str) -> Result<Vec<KeywordRow>, DataError> {
+    let mut lines: HashMap<String, u64> = HashMap::new();
+    crate::validate::read_csv(text, &["keyword", "variant"], |record| {
+        let keyword: String = record.field(0)?;
+        let variant: String = record.field(1)?;
+        if let Some(first) = lines.insert(keyword.clone(), record.line()) {
+            return Err(record.error(
+                0,
+                format!(
+                    "duplicate keyword `{}` (first defined on line {})",
+                    keyword, first
+                ),
+            ));
+        }
+        if !is_variant_name(&variant) {
+            return Err(record.error(
+                1,
+                format!(
+                    "`{}` is not a valid variant name for keyword `{}`",
+                    variant, keyword
+                ),
+            ));
+        }
+        Ok(KeywordRow {
+            line: record.line(),
+            keyword,
+            variant,
+        })
+    })
+}
+
+fn is_variant_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_uppercase() => {
+            name != "Self" && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
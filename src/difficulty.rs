@@ -0,0 +1,47 @@
+//! Difficulty levels, read from `data/difficulties.csv` at run time through
+//! `hybrid::DIFFICULTIES`. The build script includes this file with `#[path]` so that it can
+//! validate the data file with exactly the same code.
+
+use crate::validate::{read_csv, DataError};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difficulty {
+    pub name: String,
+    /// Multiplies the health of every enemy
+    pub enemy_health: f32,
+    /// Multiplies the damage of every enemy
+    pub enemy_damage: f32,
+    pub lives: u8,
+}
+
+/// Parses and validates the contents of `data/difficulties.csv`
+pub fn parse(text: &str) -> Result<Vec<Difficulty>, DataError> {
+    let mut names: Vec<String> = Vec::new();
+    read_csv(
+        text,
+        &["name", "enemy_health", "enemy_damage", "lives"],
+        |record| {
+            let name: String = record.field(0)?;
+            if names.contains(&name) {
+                return Err(record.error(0, format!("duplicate difficulty `{}`", name)));
+            }
+            names.push(name.clone());
+
+            let difficulty = Difficulty {
+                name,
+                enemy_health: record.field(1)?,
+                enemy_damage: record.field(2)?,
+                lives: record.field(3)?,
+            };
+            for (index, multiplier) in [(1, difficulty.enemy_health), (2, difficulty.enemy_damage)].iter() {
+                if !(*multiplier > 0.0 && multiplier.is_finite()) {
+                    return Err(record.error(*index, "multipliers have to be positive".to_string()));
+                }
+            }
+            if difficulty.lives == 0 {
+                return Err(record.error(3, "there has to be at least one life".to_string()));
+            }
+            Ok(difficulty)
+        },
+    )
+}
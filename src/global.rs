@@ -0,0 +1,525 @@
+//! One interface over all the ways of declaring global data
+//!
+//! Each section of the README uses a different primitive, and each primitive has its own way of
+//! reading and writing. `GlobalData` puts them behind the same four methods, so a global can move
+//! from, say, a `Lazy` to an `ArcSwap` without touching the code that uses it. Backends that can't
+//! do something return an error rather than silently ignoring the request.
+//!
+//! Inherent methods win over trait methods, so outside of generic code a few calls need spelling
+//! out: `GlobalData::get(&CELL)` for a `OnceCell`, and `GlobalData::read(&*SWAP)` for an `ArcSwap`
+//! inside a `Lazy`.
+//!
+//! The `global!` macro chooses a backend from the properties a global needs. Combinations that
+//! can't work are compile errors:
+//!
+//! ```compile_fail
+//! global_data_in_rust::global! {
+//!     // error: `reloadable` globals are loaded at run time, so they need `runtime` too
+//!     #[global(reloadable)]
+//!     static CONFIG: u32 = 8;
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! global_data_in_rust::global! {
+//!     // error: `reloadable` globals are kept in an `Arc`, which needs the heap
+//!     #[global(runtime, reloadable, no_heap)]
+//!     static CONFIG: u32 = 8;
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! global_data_in_rust::global! {
+//!     // error: a global can't be both `const` and `runtime`
+//!     #[global(const, runtime)]
+//!     static CONFIG: u32 = 8;
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! global_data_in_rust::global! {
+//!     // error: a global can't be both `const` and `mutable`
+//!     #[global(const, mutable)]
+//!     static CONFIG: u32 = 8;
+//! }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use arc_swap::{ArcSwap, Guard};
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::{const_mutex, Mutex, MutexGuard, RwLock, RwLockReadGuard};
+
+use crate::config::ReloadableConfig;
+use crate::group::Group;
+use crate::history::{RollbackError, Version, Versions, DEFAULT_HISTORY};
+use crate::lookup::{KeyMap, MatchMap, OrderedMap, SortedMap};
+use crate::subscribe::{Subscribe, Subscribers};
+
+/// A `static` that holds a value of type `Value`
+pub trait GlobalData {
+    type Value;
+    /// What `read` hands out: a reference, a lock guard or an `Arc`
+    type Guard: Deref<Target = Self::Value>;
+
+    /// Borrow the current value, initializing it first if needed
+    fn read(&'static self) -> Self::Guard;
+
+    /// Replace the current value
+    fn try_set(&'static self, value: Self::Value) -> Result<(), SetError<Self::Value>>;
+
+    /// Recompute the value from wherever it originally came from
+    fn reload(&'static self) -> Result<(), ReloadError>;
+
+    /// Clone the current value
+    fn get(&'static self) -> Self::Value
+    where
+        Self::Value: Clone,
+    {
+        self.read().clone()
+    }
+}
+
+/// Why `try_set` failed; the rejected value is handed back
+#[derive(Debug, PartialEq, Eq)]
+pub enum SetError<T> {
+    /// The backend can never be written to, e.g. a `const` or a `Lazy`
+    ReadOnly(T),
+    /// The backend can be set only once, and it has been
+    AlreadySet(T),
+}
+
+impl<T> SetError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            SetError::ReadOnly(value) | SetError::AlreadySet(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for SetError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SetError::ReadOnly(_) => write!(f, "global data is read-only"),
+            SetError::AlreadySet(_) => write!(f, "global data has already been set"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for SetError<T> {}
+
+#[derive(Debug)]
+pub enum ReloadError {
+    /// The backend has nothing to reload from
+    Unsupported,
+    /// The loader failed; the previous value is still in place
+    Failed(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReloadError::Unsupported => write!(f, "global data can't be reloaded"),
+            ReloadError::Failed(e) => write!(f, "reload failed: {}", e),
+        }
+    }
+}
+
+impl Error for ReloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReloadError::Unsupported => None,
+            ReloadError::Failed(e) => Some(&**e),
+        }
+    }
+}
+
+/// A `const` or `include_*!` value, stored in a `static` so that it has an address
+///
+/// ```
+/// use global_data_in_rust::global::{Const, GlobalData};
+///
+/// static SAMPLE: Const<&str> = Const(include_str!("../sample.txt"));
+///
+/// assert!(SAMPLE.read().starts_with("Hello"));
+/// ```
+#[derive(Debug)]
+pub struct Const<T>(pub T);
+
+impl<T: 'static> GlobalData for Const<T> {
+    type Value = T;
+    type Guard = &'static T;
+
+    fn read(&'static self) -> &'static T {
+        &self.0
+    }
+
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        Err(SetError::ReadOnly(value))
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+/// Implement `GlobalData` for a global declared with `lazy_static!`
+///
+/// `lazy_static!` generates a new type for each global, so there is nothing generic to implement
+/// the trait for. Name the global and its type instead:
+///
+/// ```
+/// use global_data_in_rust::global::GlobalData;
+/// use lazy_static::lazy_static;
+///
+/// lazy_static! {
+///     static ref GREETING: String = "hello".to_uppercase();
+/// }
+/// global_data_in_rust::lazy_static_global_data!(GREETING: String);
+///
+/// assert_eq!(GREETING.get(), "HELLO");
+/// ```
+#[macro_export]
+macro_rules! lazy_static_global_data {
+    ($name:ident : $ty:ty) => {
+        impl $crate::global::GlobalData for $name {
+            type Value = $ty;
+            type Guard = &'static $ty;
+
+            fn read(&'static self) -> &'static $ty {
+                ::std::ops::Deref::deref(self)
+            }
+
+            fn try_set(
+                &'static self,
+                value: $ty,
+            ) -> ::std::result::Result<(), $crate::global::SetError<$ty>> {
+                Err($crate::global::SetError::ReadOnly(value))
+            }
+
+            fn reload(&'static self) -> ::std::result::Result<(), $crate::global::ReloadError> {
+                Err($crate::global::ReloadError::Unsupported)
+            }
+        }
+    };
+}
+
+impl<T: 'static, F: FnOnce() -> T + 'static> GlobalData for Lazy<T, F> {
+    type Value = T;
+    type Guard = &'static T;
+
+    fn read(&'static self) -> &'static T {
+        Lazy::force(self)
+    }
+
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        Err(SetError::ReadOnly(value))
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+/// Can be set once; reading it before then panics
+impl<T: 'static> GlobalData for OnceCell<T> {
+    type Value = T;
+    type Guard = &'static T;
+
+    fn read(&'static self) -> &'static T {
+        self.get().expect("global data was read before it was set")
+    }
+
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        self.set(value).map_err(SetError::AlreadySet)
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+/// `read` holds the lock until the guard is dropped
+impl<T: 'static> GlobalData for Mutex<T> {
+    type Value = T;
+    type Guard = MutexGuard<'static, T>;
+
+    fn read(&'static self) -> MutexGuard<'static, T> {
+        self.lock()
+    }
+
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        *self.lock() = value;
+        Ok(())
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+impl<T: 'static> GlobalData for RwLock<T> {
+    type Value = T;
+    type Guard = RwLockReadGuard<'static, T>;
+
+    fn read(&'static self) -> RwLockReadGuard<'static, T> {
+        RwLock::read(self)
+    }
+
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        *self.write() = value;
+        Ok(())
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+/// A `Mutex` whose value is computed on first access, for mutable data that can't be built in a
+/// `const`
+pub struct LazyMutex<T> {
+    init: fn() -> T,
+    cell: OnceCell<Mutex<T>>,
+}
+
+impl<T> LazyMutex<T> {
+    pub const fn new(init: fn() -> T) -> Self {
+        LazyMutex {
+            init,
+            cell: OnceCell::new(),
+        }
+    }
+}
+
+impl<T> Deref for LazyMutex<T> {
+    type Target = Mutex<T>;
+
+    fn deref(&self) -> &Mutex<T> {
+        self.cell.get_or_init(|| Mutex::new((self.init)()))
+    }
+}
+
+impl<T: 'static> GlobalData for LazyMutex<T> {
+    type Value = T;
+    type Guard = MutexGuard<'static, T>;
+
+    fn read(&'static self) -> MutexGuard<'static, T> {
+        self.lock()
+    }
+
+    /// Skips the initializer if the value hasn't been computed yet
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        if let Err(mutex) = self.cell.set(Mutex::new(value)) {
+            *self.lock() = mutex.into_inner();
+        }
+        Ok(())
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+// The generated maps are read-only, and their value is the whole map, so lookups go through
+// `read`: `KEYWORDS.read().get("loop")`
+macro_rules! read_only_map {
+    ($(<$($param:ident),*> $map:ty),*) => {$(
+        impl<$($param: 'static),*> GlobalData for $map {
+            type Value = $map;
+            type Guard = &'static $map;
+
+            fn read(&'static self) -> &'static $map {
+                self
+            }
+
+            fn try_set(&'static self, value: $map) -> Result<(), SetError<$map>> {
+                Err(SetError::ReadOnly(value))
+            }
+
+            fn reload(&'static self) -> Result<(), ReloadError> {
+                Err(ReloadError::Unsupported)
+            }
+        }
+    )*};
+}
+
+read_only_map!(
+    <K, V> phf::Map<K, V>,
+    <V> SortedMap<V>,
+    <V> MatchMap<V>,
+    <V> OrderedMap<V>,
+    <K, V> KeyMap<K, V>
+);
+
+/// `read` takes a snapshot; a concurrent `try_set` doesn't affect it
+impl<T: 'static> GlobalData for ArcSwap<T> {
+    type Value = T;
+    type Guard = Arc<T>;
+
+    fn read(&'static self) -> Arc<T> {
+        self.load_full()
+    }
+
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        self.store(Arc::new(value));
+        Ok(())
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+pub type LoadFn<T> = fn() -> Result<T, Box<dyn Error + Send + Sync>>;
+
+/// An `ArcSwap` that knows how to compute its value, so it can be reloaded
+///
+/// The value is loaded on first access. If that first load fails, `read` panics. Every value
+/// after that is announced to its subscribers (see `Subscribe`), and the ones it replaces are
+/// kept for `rollback` (see `history`).
+pub struct Reloadable<T> {
+    load: LoadFn<T>,
+    current: OnceCell<Versions<T>>,
+    history: usize,
+    // Held from publishing a value until its subscribers have been told, so that they hear about
+    // values in the order they were published
+    writer: Mutex<()>,
+    subscribers: Subscribers<T>,
+}
+
+impl<T> Reloadable<T> {
+    pub const fn new(load: LoadFn<T>) -> Self {
+        Reloadable {
+            load,
+            current: OnceCell::new(),
+            history: DEFAULT_HISTORY,
+            writer: const_mutex(()),
+            subscribers: Subscribers::new(),
+        }
+    }
+
+    /// Keeps this many past versions instead of `DEFAULT_HISTORY`
+    pub const fn keep_history(mut self, versions: usize) -> Self {
+        self.history = versions;
+        self
+    }
+
+    /// The current version, which derefs to the value. Like `ArcSwap::load`, this doesn't take a
+    /// lock.
+    pub fn load(&self) -> Guard<'static, Arc<Version<T>>> {
+        self.current().load()
+    }
+
+    /// The current version followed by the past ones, newest first
+    pub fn history(&self) -> Vec<Arc<Version<T>>> {
+        self.current().history()
+    }
+
+    /// Publishes the value from `n` versions ago again, as a new version
+    pub fn rollback(&self, n: usize) -> Result<(), RollbackError> {
+        let current = self.current();
+        self.subscribers.check_not_notifying();
+        let _writer = self.writer.lock();
+        let (old, new) = current.rollback(n)?;
+        self.subscribers.notify(&old.value, &new.value);
+        Ok(())
+    }
+
+    fn publish(&self, value: T, source: &'static str) {
+        let value = Arc::new(value);
+        let mut first = false;
+        let current = self.current.get_or_init(|| {
+            first = true;
+            Versions::new(value.clone(), source, self.history)
+        });
+        if !first {
+            self.subscribers.check_not_notifying();
+            let _writer = self.writer.lock();
+            let (old, new) = current.publish(value, source);
+            self.subscribers.notify(&old.value, &new.value);
+        }
+    }
+
+    fn current(&self) -> &Versions<T> {
+        self.current.get_or_init(|| match (self.load)() {
+            Ok(value) => Versions::new(Arc::new(value), "initial load", self.history),
+            Err(e) => panic!("failed to load global data: {}", e),
+        })
+    }
+}
+
+impl<T: 'static> GlobalData for Reloadable<T> {
+    type Value = T;
+    type Guard = Arc<T>;
+
+    fn read(&'static self) -> Arc<T> {
+        self.load().value.clone()
+    }
+
+    /// The value stays in place until the next `reload`
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        self.publish(value, "try_set");
+        Ok(())
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        self.publish((self.load)().map_err(ReloadError::Failed)?, "reload");
+        Ok(())
+    }
+}
+
+impl<T: 'static> Subscribe for Reloadable<T> {
+    type Value = T;
+
+    fn subscribers(&self) -> &Subscribers<T> {
+        &self.subscribers
+    }
+}
+
+/// The file is the only source of values, so `try_set` is rejected. Write the file instead.
+impl<T: 'static> GlobalData for ReloadableConfig<T> {
+    type Value = T;
+    type Guard = Arc<T>;
+
+    fn read(&'static self) -> Arc<T> {
+        self.load().value.clone()
+    }
+
+    fn try_set(&'static self, value: T) -> Result<(), SetError<T>> {
+        Err(SetError::ReadOnly(value))
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        ReloadableConfig::reload(self).map_err(|e| ReloadError::Failed(Box::new(e)))
+    }
+}
+
+/// `try_set` replaces every global in the group in one commit
+impl<S: Clone + 'static> GlobalData for Group<S> {
+    type Value = S;
+    type Guard = Arc<S>;
+
+    fn read(&'static self) -> Arc<S> {
+        self.snapshot().value.clone()
+    }
+
+    fn try_set(&'static self, value: S) -> Result<(), SetError<S>> {
+        self.commit(|globals| *globals = value);
+        Ok(())
+    }
+
+    fn reload(&'static self) -> Result<(), ReloadError> {
+        Err(ReloadError::Unsupported)
+    }
+}
+
+// Used by `global!`, which can only refer to this crate by name
+#[doc(hidden)]
+pub mod __private {
+    pub use once_cell::sync::Lazy;
+    pub use parking_lot::{const_mutex, Mutex};
+}
@@ -0,0 +1,126 @@
+//! Several globals that change together
+//!
+//! Reading two `ArcSwap` globals one after the other can see the first one before an update and
+//! the second one after it. When globals have to agree with each other, like a routing table and
+//! the limits for each route, put them in one `Group` instead. The group's value is a struct
+//! with an `Arc` for each global, so the group itself is a single `ArcSwap`:
+//!
+//! - `snapshot` loads all of them at once, along with the generation they belong to.
+//! - `commit` updates any of them, and readers see either all of the updates or none of them.
+//!
+//! Like `Reloadable`, a group keeps its past versions for `rollback` and tells its subscribers
+//! about every commit.
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use global_data_in_rust::group::Group;
+//! use once_cell::sync::Lazy;
+//!
+//! #[derive(Clone)]
+//! struct Routing {
+//!     backends: Arc<Vec<&'static str>>,
+//!     max_connections: Arc<Vec<u32>>,
+//! }
+//!
+//! static ROUTING: Lazy<Group<Routing>> = Lazy::new(|| {
+//!     Group::new(Routing {
+//!         backends: Arc::new(vec!["a"]),
+//!         max_connections: Arc::new(vec![100]),
+//!     })
+//! });
+//!
+//! ROUTING.commit(|routing| {
+//!     routing.backends = Arc::new(vec!["a", "b"]);
+//!     routing.max_connections = Arc::new(vec![100, 50]);
+//! });
+//! let snapshot = ROUTING.snapshot();
+//! assert_eq!(snapshot.generation, 1);
+//! assert_eq!(snapshot.backends.len(), snapshot.max_connections.len());
+//! ```
+
+use std::sync::Arc;
+
+use arc_swap::Guard;
+use parking_lot::Mutex;
+
+use crate::history::{RollbackError, Version, Versions, DEFAULT_HISTORY};
+use crate::subscribe::{Subscribe, Subscribers};
+
+/// Globals that are read and written together
+pub struct Group<S> {
+    versions: Versions<S>,
+    // Held for the whole of a commit, so that a commit can't lose a concurrent one's updates
+    writer: Mutex<()>,
+    subscribers: Subscribers<S>,
+}
+
+impl<S: Clone> Group<S> {
+    pub fn new(globals: S) -> Self {
+        Group {
+            versions: Versions::new(Arc::new(globals), "initial value", DEFAULT_HISTORY),
+            writer: Mutex::new(()),
+            subscribers: Subscribers::new(),
+        }
+    }
+
+    /// Keeps this many past versions instead of `DEFAULT_HISTORY`
+    pub fn keep_history(mut self, versions: usize) -> Self {
+        self.versions.set_capacity(versions);
+        self
+    }
+
+    /// Every global in the group, as of one commit. Like `ArcSwap::load`, this doesn't take a
+    /// lock, and later commits don't affect it.
+    pub fn snapshot(&self) -> Guard<'static, Arc<Version<S>>> {
+        self.versions.load()
+    }
+
+    /// Updates a copy of the current globals with `update` and publishes it as one new version.
+    /// Returns its generation.
+    ///
+    /// Commits happen one at a time, so `update` shouldn't take long, and it mustn't commit to
+    /// the same group.
+    pub fn commit(&self, update: impl FnOnce(&mut S)) -> u64 {
+        match self.try_commit(|globals| {
+            update(globals);
+            Ok::<(), std::convert::Infallible>(())
+        }) {
+            Ok(generation) => generation,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Like `commit`, but if `update` fails, nothing is published and the error is returned
+    pub fn try_commit<E>(&self, update: impl FnOnce(&mut S) -> Result<(), E>) -> Result<u64, E> {
+        self.subscribers.check_not_notifying();
+        let _writer = self.writer.lock();
+        let mut globals = S::clone(&self.versions.load().value);
+        update(&mut globals)?;
+        let (old, new) = self.versions.publish(Arc::new(globals), "commit");
+        self.subscribers.notify(&old.value, &new.value);
+        Ok(new.generation)
+    }
+
+    /// The current version followed by the past ones, newest first
+    pub fn history(&self) -> Vec<Arc<Version<S>>> {
+        self.versions.history()
+    }
+
+    /// Publishes the globals from `n` commits ago again, as a new version
+    pub fn rollback(&self, n: usize) -> Result<(), RollbackError> {
+        self.subscribers.check_not_notifying();
+        let _writer = self.writer.lock();
+        let (old, new) = self.versions.rollback(n)?;
+        self.subscribers.notify(&old.value, &new.value);
+        Ok(())
+    }
+}
+
+impl<S: 'static> Subscribe for Group<S> {
+    type Value = S;
+
+    fn subscribers(&self) -> &Subscribers<S> {
+        &self.subscribers
+    }
+}
@@ -0,0 +1,140 @@
+//! Past versions of a reloadable global, for finding out what changed and rolling back
+//!
+//! Every value that `Reloadable` or `ReloadableConfig` publishes becomes a `Version`, with a
+//! generation number that goes up by one each time, the time it was published, and where it came
+//! from. The current version is what `load` returns, so the generation of a value can be read
+//! without any chance of it belonging to a different value. A bounded number of past versions
+//! is kept, and `rollback` publishes one of them again.
+//!
+//! A rollback is a new version too, with a new generation number. Generations never go
+//! backwards, so anything that remembers a generation can tell that the value has changed since.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use arc_swap::{ArcSwap, Guard};
+use parking_lot::Mutex;
+
+/// How many past versions are kept unless the global asks for a different number
+pub const DEFAULT_HISTORY: usize = 8;
+
+/// One value of a global, along with where it came from
+#[derive(Debug)]
+pub struct Version<T> {
+    /// Starts at 0 for the first value, and goes up by one with each new value
+    pub generation: u64,
+    pub published: SystemTime,
+    /// What published this value, e.g. `reloaded data/config.toml`
+    pub source: Cow<'static, str>,
+    pub value: Arc<T>,
+}
+
+impl<T> Deref for Version<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// The error returned when rolling back further than the history goes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RollbackError {
+    pub requested: usize,
+    pub available: usize,
+}
+
+impl fmt::Display for RollbackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "can't roll back {} versions, only {} past versions are kept",
+            self.requested, self.available
+        )
+    }
+}
+
+impl Error for RollbackError {}
+
+// The version that was replaced, and the one that replaced it
+type Replaced<T> = (Arc<Version<T>>, Arc<Version<T>>);
+
+/// The current version of a global, and the ones before it
+pub(crate) struct Versions<T> {
+    current: ArcSwap<Version<T>>,
+    // Newest first. The lock is held while publishing, so that generations are published in order.
+    past: Mutex<VecDeque<Arc<Version<T>>>>,
+    capacity: usize,
+}
+
+impl<T> Versions<T> {
+    pub fn new(value: Arc<T>, source: impl Into<Cow<'static, str>>, capacity: usize) -> Self {
+        Versions {
+            current: ArcSwap::from_pointee(Version {
+                generation: 0,
+                published: SystemTime::now(),
+                source: source.into(),
+                value,
+            }),
+            past: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.past.get_mut().truncate(capacity);
+        self.capacity = capacity;
+    }
+
+    pub fn load(&self) -> Guard<'static, Arc<Version<T>>> {
+        self.current.load()
+    }
+
+    /// Makes `value` the current version
+    pub fn publish(&self, value: Arc<T>, source: impl Into<Cow<'static, str>>) -> Replaced<T> {
+        let mut past = self.past.lock();
+        let new = Arc::new(Version {
+            generation: self.current.load().generation + 1,
+            published: SystemTime::now(),
+            source: source.into(),
+            value,
+        });
+        let old = self.current.swap(new.clone());
+        if self.capacity > 0 {
+            past.truncate(self.capacity - 1);
+            past.push_front(old.clone());
+        }
+        (old, new)
+    }
+
+    /// Publishes the value from `n` versions ago again
+    pub fn rollback(&self, n: usize) -> Result<Replaced<T>, RollbackError> {
+        let target = {
+            let past = self.past.lock();
+            match n.checked_sub(1).and_then(|i| past.get(i)) {
+                Some(version) => version.clone(),
+                None => {
+                    return Err(RollbackError {
+                        requested: n,
+                        available: past.len(),
+                    })
+                }
+            }
+        };
+        let source = format!("rollback to generation {}", target.generation);
+        Ok(self.publish(target.value.clone(), source))
+    }
+
+    /// The current version followed by the past ones, newest first
+    pub fn history(&self) -> Vec<Arc<Version<T>>> {
+        let past = self.past.lock();
+        let mut history = vec![self.current.load_full()];
+        history.extend(past.iter().cloned());
+        history
+    }
+}
@@ -0,0 +1,107 @@
+//! Data that is validated at compile time but loaded at run time
+//!
+//! The build script validates each data file and embeds a hash of its contents. At run time,
+//! `Hybrid` reads the file from disk. If the hash still matches, the file is the one that was
+//! validated, so it can't fail to parse. If the file has been edited since, it gets validated
+//! again and any problem is reported with its position. Either way, editing the data doesn't
+//! force a recompile.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+
+use crate::difficulty::{self, Difficulty};
+use crate::validate::{content_hash, DataError};
+
+include!(concat!(env!("OUT_DIR"), "/hybrid.rs"));
+
+/// The difficulty levels from `data/difficulties.csv`
+pub static DIFFICULTIES: Hybrid<Vec<Difficulty>> =
+    Hybrid::new("data/difficulties.csv", DIFFICULTIES_HASH, difficulty::parse);
+
+/// Whether the loaded file was the one that the build script validated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// The contents matched the hash from the build script
+    Validated,
+    /// The file had been edited since the build, so it was validated at run time
+    Revalidated,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(PathBuf, io::Error),
+    Invalid(PathBuf, DataError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            LoadError::Invalid(path, e) => write!(f, "{}:{}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// A `'static` handle to data that is loaded from disk once, usually at startup
+pub struct Hybrid<T: 'static> {
+    path: &'static str,
+    hash: u64,
+    parse: fn(&str) -> Result<T, DataError>,
+    cell: OnceCell<(T, Source)>,
+}
+
+impl<T> Hybrid<T> {
+    /// `hash` is the `content_hash` of the file at `path` when the build script validated it
+    /// with `parse`.
+    pub const fn new(path: &'static str, hash: u64, parse: fn(&str) -> Result<T, DataError>) -> Self {
+        Hybrid {
+            path,
+            hash,
+            parse,
+            cell: OnceCell::new(),
+        }
+    }
+
+    /// Loads the data from the path that the build script validated. Once the data has loaded,
+    /// later calls return it without touching the disk.
+    pub fn load(&'static self) -> Result<&'static T, LoadError> {
+        self.load_from(self.path)
+    }
+
+    /// Like `load`, but reads from a different path, e.g. next to an installed binary
+    pub fn load_from(&'static self, path: impl AsRef<Path>) -> Result<&'static T, LoadError> {
+        let path = path.as_ref();
+        let (data, _) = self.cell.get_or_try_init(|| {
+            let text = fs::read_to_string(path).map_err(|e| LoadError::Io(path.to_owned(), e))?;
+            if content_hash(text.as_bytes()) == self.hash {
+                let data = (self.parse)(&text).expect("the build script validated this file");
+                Ok((data, Source::Validated))
+            } else {
+                let data = (self.parse)(&text).map_err(|e| LoadError::Invalid(path.to_owned(), e))?;
+                Ok((data, Source::Revalidated))
+            }
+        })?;
+        Ok(data)
+    }
+
+    /// The loaded data, loading it first if needed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data hasn't been loaded yet and loading it fails. Call `load` at startup to
+    /// handle that error instead.
+    pub fn get(&'static self) -> &'static T {
+        self.load().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// How the data was loaded, or `None` if it hasn't been loaded yet
+    pub fn source(&self) -> Option<Source> {
+        self.cell.get().map(|&(_, source)| source)
+    }
+}
@@ -0,0 +1,112 @@
+//! Build-time checksums for embedded data, to detect a corrupted or tampered binary
+//!
+//! The build script computes the SHA-256 and length of every embedded blob. `verify_all` checks
+//! them again at run time, which is worth doing once at startup. The hashes also make handy
+//! version numbers for the data, e.g. in logs.
+
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+
+use crate::assets::ASSETS;
+
+include!(concat!(env!("OUT_DIR"), "/integrity.rs"));
+
+/// An embedded blob, along with its length and hash when the crate was built
+pub trait Blob {
+    const NAME: &'static str;
+    const BYTES: &'static [u8];
+    const LEN: usize;
+    const SHA256: [u8; 32];
+
+    fn blob_ref() -> BlobRef {
+        BlobRef {
+            name: Self::NAME,
+            bytes: Self::BYTES,
+            len: Self::LEN,
+            sha256: Self::SHA256,
+        }
+    }
+}
+
+/// The same information as a `Blob` impl, but as a value
+#[derive(Clone, Copy, Debug)]
+pub struct BlobRef {
+    pub name: &'static str,
+    pub bytes: &'static [u8],
+    pub len: usize,
+    pub sha256: [u8; 32],
+}
+
+impl BlobRef {
+    /// Checks the bytes against the length and hash from the build
+    pub fn verify(&self) -> Result<(), IntegrityError> {
+        let actual: [u8; 32] = Sha256::digest(self.bytes).into();
+        if self.bytes.len() == self.len && actual == self.sha256 {
+            Ok(())
+        } else {
+            Err(IntegrityError {
+                name: self.name,
+                expected: self.sha256,
+                actual,
+            })
+        }
+    }
+
+    /// A short version string for the data, e.g. for logs
+    pub fn version(&self) -> String {
+        hex(&self.sha256[..8])
+    }
+}
+
+/// An embedded blob no longer matches its build-time hash
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityError {
+    pub name: &'static str,
+    pub expected: [u8; 32],
+    pub actual: [u8; 32],
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} has SHA-256 {}, but it was {} when it was built",
+            self.name,
+            hex(&self.actual),
+            hex(&self.expected)
+        )
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// Every embedded blob, including each file in `assets::ASSETS`
+pub fn blobs() -> Vec<BlobRef> {
+    let mut blobs = generated_blobs();
+    blobs.extend(ASSETS.embedded().map(|(path, file)| BlobRef {
+        name: path,
+        bytes: file.contents,
+        len: file.size,
+        sha256: file.sha256,
+    }));
+    blobs
+}
+
+/// Checks every embedded blob, and returns every one that doesn't match
+pub fn verify_all() -> Result<(), Vec<IntegrityError>> {
+    let errors: Vec<IntegrityError> = blobs()
+        .iter()
+        .filter_map(|blob| blob.verify().err())
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Formats bytes as lowercase hexadecimal
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,92 @@
+//! The keyword table that `build/keywords.rs` generates from `data/keywords.csv`
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+use arc_swap::ArcSwap;
+use once_cell::sync::Lazy;
+
+use crate::perfect_map::PerfectMap;
+use crate::validate::{read_csv, DataError};
+
+include!(concat!(env!("OUT_DIR"), "/keywords.rs"));
+
+/// Looks up a keyword the way a user might type it, ignoring surrounding whitespace and ASCII
+/// case. With the `nfc` feature, the input is also normalized to Unicode NFC.
+///
+/// The keys were normalized in the same way by the build script, so this is still a single phf
+/// lookup. The input is normalized into a buffer on the stack rather than a `String`.
+pub fn get_normalized(text: &str) -> Option<Keyword2> {
+    let mut buf = [0; MAX_NORMALIZED_LEN];
+    let mut len = 0;
+    for c in normalized_chars(text.trim()) {
+        let c = c.to_ascii_lowercase();
+        // Anything longer than the longest key can't match, so there's no need to keep going
+        if len + c.len_utf8() > buf.len() {
+            return None;
+        }
+        len += c.encode_utf8(&mut buf[len..]).len();
+    }
+    let normalized = std::str::from_utf8(&buf[..len]).ok()?;
+    KEYWORDS_NORMALIZED.get(normalized).copied()
+}
+
+/// Keywords that look like a misspelling of `text`, closest first. Handy for error messages
+/// when `KEYWORDS.get(text)` comes back empty.
+pub fn suggest(text: &str) -> Vec<&'static str> {
+    crate::suggest::did_you_mean(&KEYWORDS, text)
+}
+
+/// The keyword table as a `PerfectMap`, which `reload` can replace without a rebuild, e.g. to add
+/// aliases. It starts out with the same entries as `KEYWORDS`.
+pub static LIVE_KEYWORDS: Lazy<ArcSwap<PerfectMap<String, Keyword2>>> = Lazy::new(|| {
+    let entries = KEYWORDS
+        .entries()
+        .map(|(k, v)| (k.to_string(), *v))
+        .collect();
+    ArcSwap::from_pointee(PerfectMap::new(entries).unwrap())
+});
+
+/// Replaces `LIVE_KEYWORDS` with a table in the same format as `data/keywords.csv`. Readers see
+/// either the old table or the new one, and they're never blocked while it's being built.
+pub fn reload(csv: &str) -> Result<(), DataError> {
+    let mut seen = HashSet::new();
+    let entries = read_csv(csv, &["keyword", "variant"], |record| {
+        let keyword: String = record.field(0)?;
+        let variant: String = record.field(1)?;
+        if !seen.insert(keyword.clone()) {
+            return Err(record.error(0, format!("duplicate keyword `{}`", keyword)));
+        }
+        // The variants are fixed at compile time, only the text that maps to them can change
+        match Keyword2::from_variant_name(&variant) {
+            Some(keyword2) => Ok((keyword, keyword2)),
+            None => Err(record.error(1, format!("there is no variant `{}`", variant))),
+        }
+    })?;
+    let map = PerfectMap::new(entries).expect("duplicates were already rejected");
+    LIVE_KEYWORDS.store(map.into());
+    Ok(())
+}
+
+#[cfg(feature = "nfc")]
+fn normalized_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    unicode_normalization::UnicodeNormalization::nfc(text)
+}
+
+#[cfg(not(feature = "nfc"))]
+fn normalized_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars()
+}
+
+/// The error returned when parsing a string that isn't a keyword
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseKeywordError(());
+
+impl fmt::Display for ParseKeywordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("not a keyword")
+    }
+}
+
+impl Error for ParseKeywordError {}
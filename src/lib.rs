@@ -1,6 +1,52 @@
 // I put this in lib.rs because the path from skeptic was weird
+#[allow(clippy::redundant_static_lifetimes)]
 pub const SAMPLE_STR: &'static str = include_str!("../sample.txt");
+#[allow(clippy::redundant_static_lifetimes)]
 pub const SAMPLE_BYTES: &'static [u8] = include_bytes!("../sample.txt");
 
 pub const SIX: i8 = 6;
 pub const ALSO_SIX: i8 = include!("six.rs-snippet");
+
+pub use global_data_macros::global;
+
+/// Errors in the file are compile errors that point at its path, rather than panics:
+///
+/// ```compile_fail
+/// struct Config {
+///     max_players: u8,
+/// }
+///
+/// // error: tests/bad_field_name.toml: `max-players` is not a valid field name
+/// static CONFIG: Config = global_data_in_rust::include_data!("tests/bad_field_name.toml" as Config {});
+/// ```
+///
+/// ```compile_fail
+/// struct Config {
+///     tick_rate: f32,
+/// }
+///
+/// // error: tests/infinite_float.toml: `tick_rate` is inf, which isn't supported
+/// static CONFIG: Config = global_data_in_rust::include_data!("tests/infinite_float.toml" as Config {});
+/// ```
+pub use global_data_macros::include_data;
+
+pub mod assets;
+pub mod compressed;
+pub mod config;
+pub mod data_files;
+pub mod difficulty;
+pub mod global;
+pub mod group;
+pub mod history;
+pub mod hybrid;
+pub mod integrity;
+pub mod keywords;
+pub mod lookup;
+pub mod perfect_map;
+#[cfg(unix)]
+pub mod sighup;
+pub mod subscribe;
+pub mod suggest;
+pub mod tables;
+pub mod tokenizer;
+pub mod validate;
@@ -0,0 +1,276 @@
+//! The maps that the build script can generate instead of a `phf::Map`
+//!
+//! `SortedMap`, `MatchMap` and `OrderedMap` have the same lookup and iteration methods as
+//! `phf::Map`, so a table can be switched between them in `build/main.rs` without changing the
+//! code that uses it. Code that has to work with any of them can use the `StrMap` trait.
+//!
+//! Tables that aren't keyed by strings use a `KeyMap`, which works with any `Key`: integers,
+//! pairs of them, and generated enums like `Keyword2`.
+
+/// A map that's searched by binary search
+pub struct SortedMap<V: 'static> {
+    entries: &'static [(&'static str, V)],
+}
+
+impl<V> SortedMap<V> {
+    /// `entries` has to be sorted by key, with no duplicates
+    pub const fn new(entries: &'static [(&'static str, V)]) -> Self {
+        SortedMap { entries }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.entries
+            .binary_search_by_key(&key, |&(k, _)| k)
+            .ok()
+            .map(|i| &self.entries[i].1)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// In order of key
+    pub fn entries(&self) -> impl Iterator<Item = (&&'static str, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &&'static str> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+/// A map whose lookups go through a generated `match` on the key
+pub struct MatchMap<V: 'static> {
+    entries: &'static [(&'static str, V)],
+    index: fn(&str) -> Option<usize>,
+}
+
+impl<V> MatchMap<V> {
+    /// `index` returns the position of a key in `entries`
+    pub const fn new(
+        entries: &'static [(&'static str, V)],
+        index: fn(&str) -> Option<usize>,
+    ) -> Self {
+        MatchMap { entries, index }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&V> {
+        (self.index)(key).map(|i| &self.entries[i].1)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        (self.index)(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// In file order
+    pub fn entries(&self) -> impl Iterator<Item = (&&'static str, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &&'static str> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+/// A perfect hash map that iterates in file order
+pub struct OrderedMap<V: 'static> {
+    entries: &'static [(&'static str, V)],
+    index: phf::Map<&'static str, usize>,
+}
+
+impl<V> OrderedMap<V> {
+    /// `index` maps each key to its position in `entries`
+    pub const fn new(
+        entries: &'static [(&'static str, V)],
+        index: phf::Map<&'static str, usize>,
+    ) -> Self {
+        OrderedMap { entries, index }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.index.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// In file order
+    pub fn entries(&self) -> impl Iterator<Item = (&&'static str, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &&'static str> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+/// A key type for `KeyMap`
+///
+/// The build script hashes every key as a `u64`, so each key type packs itself into the low
+/// `BITS` bits of one. Generated enums pack their discriminant.
+pub trait Key: Copy + Eq + 'static {
+    const BITS: u32;
+
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! int_key {
+    ($($int:ty => $unsigned:ty),*) => {$(
+        impl Key for $int {
+            const BITS: u32 = <$unsigned>::BITS;
+
+            fn to_u64(self) -> u64 {
+                self as $unsigned as u64
+            }
+        }
+    )*};
+}
+
+int_key!(u8 => u8, u16 => u16, u32 => u32, u64 => u64, i8 => u8, i16 => u16, i32 => u32, i64 => u64);
+
+/// Packs `A` above `B`. The build script rejects pairs that don't fit in 64 bits.
+impl<A: Key, B: Key> Key for (A, B) {
+    const BITS: u32 = A::BITS + B::BITS;
+
+    fn to_u64(self) -> u64 {
+        self.0.to_u64() << B::BITS | self.1.to_u64()
+    }
+}
+
+/// A perfect hash map with `Key` keys, which iterates in file order
+pub struct KeyMap<K: 'static, V: 'static> {
+    entries: &'static [(K, V)],
+    index: phf::Map<u64, usize>,
+}
+
+impl<K, V> KeyMap<K, V> {
+    /// `index` maps the `to_u64` of each key to its position in `entries`
+    pub const fn new(entries: &'static [(K, V)], index: phf::Map<u64, usize>) -> Self {
+        KeyMap { entries, index }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// In file order
+    pub fn entries(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: Key, V> KeyMap<K, V> {
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.index.get(&key.to_u64()).map(|&i| &self.entries[i].1)
+    }
+
+    pub fn contains_key(&self, key: K) -> bool {
+        self.index.contains_key(&key.to_u64())
+    }
+}
+
+/// Any of the generated maps with string keys
+pub trait StrMap {
+    type Value;
+
+    fn get(&self, key: &str) -> Option<&Self::Value>;
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &'static str> + '_>;
+}
+
+impl<V> StrMap for phf::Map<&'static str, V> {
+    type Value = V;
+
+    fn get(&self, key: &str) -> Option<&V> {
+        phf::Map::get(self, key)
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &'static str> + '_> {
+        Box::new(phf::Map::keys(self).copied())
+    }
+}
+
+impl<V> StrMap for SortedMap<V> {
+    type Value = V;
+
+    fn get(&self, key: &str) -> Option<&V> {
+        SortedMap::get(self, key)
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &'static str> + '_> {
+        Box::new(SortedMap::keys(self).copied())
+    }
+}
+
+impl<V> StrMap for MatchMap<V> {
+    type Value = V;
+
+    fn get(&self, key: &str) -> Option<&V> {
+        MatchMap::get(self, key)
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &'static str> + '_> {
+        Box::new(MatchMap::keys(self).copied())
+    }
+}
+
+impl<V> StrMap for OrderedMap<V> {
+    type Value = V;
+
+    fn get(&self, key: &str) -> Option<&V> {
+        OrderedMap::get(self, key)
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &'static str> + '_> {
+        Box::new(OrderedMap::keys(self).copied())
+    }
+}
@@ -1,16 +1,5 @@
-extern crate phf;
-
-#[derive(Clone, Debug, PartialEq)]
-enum Keyword2 {
-    Loop,
-    Continue,
-    Break,
-    Fn,
-    Extern,
-}
-
-include!(concat!(env!("OUT_DIR"), "/phf.rs"));
+use global_data_in_rust::keywords::{Keyword2, KEYWORDS};
 
 fn main() {
-    assert_eq!(KEYWORDS.get("loop"), Some(&crate::Keyword2::Loop))
+    assert_eq!(KEYWORDS.get("loop"), Some(&Keyword2::Loop))
 }
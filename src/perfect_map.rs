@@ -0,0 +1,135 @@
+//! Perfect hash maps that are built at run time
+//!
+//! The build script can only generate a `phf::Map` from data that it sees at compile time.
+//! `PerfectMap` is built from data loaded at run time, using the same generator and hash
+//! function, so it has the same `get` and iteration methods. To replace one while other threads
+//! are reading it, publish it through an `ArcSwap`: readers `load` the current map without
+//! taking a lock, and a reload builds the new map first and then `store`s it in one step.
+//!
+//! ```
+//! use arc_swap::ArcSwap;
+//! use global_data_in_rust::perfect_map::PerfectMap;
+//! use once_cell::sync::Lazy;
+//!
+//! static PORTS: Lazy<ArcSwap<PerfectMap<String, u16>>> = Lazy::new(|| {
+//!     let ports = vec![("http".to_string(), 80)];
+//!     ArcSwap::from_pointee(PerfectMap::new(ports).unwrap())
+//! });
+//!
+//! let reloaded = vec![("http".to_string(), 8080), ("https".to_string(), 8443)];
+//! PORTS.store(PerfectMap::new(reloaded).unwrap().into());
+//! assert_eq!(PORTS.load().get("https"), Some(&8443));
+//! ```
+
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+use phf_shared::{HashKey, PhfHash};
+
+/// A perfect hash map, like `phf::Map`, that owns its keys and values
+pub struct PerfectMap<K, V> {
+    key: HashKey,
+    disps: Vec<(u32, u32)>,
+    // In the order of the hash table, like in `phf::Map`
+    entries: Vec<(K, V)>,
+}
+
+impl<K: PhfHash + Eq + Hash, V> PerfectMap<K, V> {
+    /// Builds the hash table, which takes time roughly linear in the number of entries
+    pub fn new(entries: Vec<(K, V)>) -> Result<Self, DuplicateKey<K>> {
+        // The generator never finishes if two keys are the same, so this has to check first
+        let mut seen = HashSet::with_capacity(entries.len());
+        if let Some(i) = entries.iter().position(|(key, _)| !seen.insert(key)) {
+            return Err(DuplicateKey(entries.into_iter().nth(i).unwrap().0));
+        }
+
+        let state =
+            phf_generator::generate_hash(&entries.iter().map(|(key, _)| key).collect::<Vec<_>>());
+        let mut slots: Vec<Option<(K, V)>> = entries.into_iter().map(Some).collect();
+        Ok(PerfectMap {
+            key: state.key,
+            disps: state.disps,
+            entries: state
+                .map
+                .iter()
+                .map(|&i| slots[i].take().unwrap())
+                .collect(),
+        })
+    }
+}
+
+impl<K, V> PerfectMap<K, V> {
+    pub fn get<T: ?Sized + Eq + PhfHash>(&self, key: &T) -> Option<&V>
+    where
+        K: Borrow<T>,
+    {
+        self.get_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn get_entry<T: ?Sized + Eq + PhfHash>(&self, key: &T) -> Option<(&K, &V)>
+    where
+        K: Borrow<T>,
+    {
+        // There's nothing to index `disps` with in an empty map
+        if self.disps.is_empty() {
+            return None;
+        }
+        let hashes = phf_shared::hash(key, &self.key);
+        let index = phf_shared::get_index(&hashes, &self.disps, self.entries.len());
+        let (k, v) = &self.entries[index as usize];
+        if k.borrow() == key {
+            Some((k, v))
+        } else {
+            None
+        }
+    }
+
+    pub fn contains_key<T: ?Sized + Eq + PhfHash>(&self, key: &T) -> bool
+    where
+        K: Borrow<T>,
+    {
+        self.get_entry(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// In an arbitrary but fixed order, like `phf::Map`
+    pub fn entries(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for PerfectMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.entries()).finish()
+    }
+}
+
+/// The error returned when building a `PerfectMap` with a key that appears more than once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateKey<K>(pub K);
+
+impl<K: fmt::Debug> fmt::Display for DuplicateKey<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "duplicate key {:?}", self.0)
+    }
+}
+
+impl<K: fmt::Debug> Error for DuplicateKey<K> {}
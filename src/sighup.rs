@@ -0,0 +1,90 @@
+//! Reloading global data when the process gets SIGHUP, the way Unix daemons usually do
+//!
+//! Nothing happens until you call `reload_on_sighup` or `on_sighup`. The signal handler that they
+//! install only writes a byte to a pipe, which is all that's safe to do inside a signal handler.
+//! A dedicated thread reads from the pipe and does the actual reload, so parsing, allocating and
+//! taking locks all happen outside the handler. Signals that arrive while a reload is running are
+//! merged into one more reload afterwards.
+
+use std::error::Error;
+use std::io;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::SystemTime;
+
+use parking_lot::Mutex;
+use signal_hook::consts::SIGHUP;
+use signal_hook::iterator::{Handle, Signals};
+
+use crate::global::GlobalData;
+
+/// The outcome of the most recent reload
+#[derive(Clone, Debug, Default)]
+pub struct ReloadStatus {
+    /// How many reloads have run, successful or not
+    pub reloads: u64,
+    /// When the most recent reload finished
+    pub last_reload: Option<SystemTime>,
+    /// Why the most recent reload failed, or `None` if it succeeded
+    pub error: Option<Arc<dyn Error + Send + Sync>>,
+}
+
+/// The thread started by `on_sighup`
+///
+/// Dropping it stops the thread, and SIGHUP no longer causes a reload.
+pub struct SighupReload {
+    status: Arc<Mutex<ReloadStatus>>,
+    handle: Handle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SighupReload {
+    pub fn status(&self) -> ReloadStatus {
+        self.status.lock().clone()
+    }
+}
+
+impl Drop for SighupReload {
+    fn drop(&mut self) {
+        self.handle.close();
+        if let Some(thread) = self.thread.take() {
+            // A panic in `reload` has already been reported by the thread
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Calls `global.reload()` on every SIGHUP. This works with any `GlobalData` that supports
+/// reloading, like `Reloadable` and `ReloadableConfig`.
+pub fn reload_on_sighup<G: GlobalData + Sync>(global: &'static G) -> io::Result<SighupReload> {
+    on_sighup(move || global.reload())
+}
+
+/// Calls `reload` on a dedicated thread on every SIGHUP, e.g. to reload several globals
+pub fn on_sighup<E: Error + Send + Sync + 'static>(
+    mut reload: impl FnMut() -> Result<(), E> + Send + 'static,
+) -> io::Result<SighupReload> {
+    let mut signals = Signals::new([SIGHUP])?;
+    let handle = signals.handle();
+    let status = Arc::new(Mutex::new(ReloadStatus::default()));
+    let thread = {
+        let status = status.clone();
+        thread::Builder::new()
+            .name("sighup-reload".to_string())
+            .spawn(move || {
+                // This ends when the handle is closed
+                for _ in signals.forever() {
+                    let error = reload().err().map(|e| Arc::new(e) as Arc<_>);
+                    let mut status = status.lock();
+                    status.reloads += 1;
+                    status.last_reload = Some(SystemTime::now());
+                    status.error = error;
+                }
+            })?
+    };
+    Ok(SighupReload {
+        status,
+        handle,
+        thread: Some(thread),
+    })
+}
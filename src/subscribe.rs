@@ -0,0 +1,191 @@
+//! Finding out when a reloadable global changes
+//!
+//! `Reloadable` and `ReloadableConfig` tell their subscribers every time they publish a new value,
+//! whether that's from a reload, a file change or a `try_set`. There are three ways to listen:
+//!
+//! - `subscribe` runs a callback with the old and the new value.
+//! - `subscribe_field` does the same for one part of the value, and only when that part changed.
+//! - `changes` returns a receiver, which can be iterated over on a thread of its own.
+//!
+//! Callbacks run on the thread that published the value, before the publishing call returns.
+//! They run while the global is locked, so that subscribers hear about values in order. That
+//! means a callback can't publish a value to the global it was called for, subscribe to it or
+//! unsubscribe from it. Trying to panics, rather than deadlocking.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::{const_mutex, Mutex};
+
+// Returns false once the subscriber has gone away, so that it can be removed
+type Callback<T> = Box<dyn FnMut(&Arc<T>, &Arc<T>) -> bool + Send>;
+
+thread_local! {
+    // The addresses of the `Subscribers` whose callbacks this thread is running
+    static NOTIFYING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+// Removes a `Subscribers` from `NOTIFYING` when its callbacks are done, even if one panics
+struct Notifying(usize);
+
+impl Drop for Notifying {
+    fn drop(&mut self) {
+        NOTIFYING.with(|notifying| notifying.borrow_mut().retain(|&a| a != self.0));
+    }
+}
+
+/// Identifies a subscription, for `unsubscribe`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// The callbacks that a global runs when it changes
+pub struct Subscribers<T> {
+    next_id: AtomicU64,
+    callbacks: Mutex<Vec<(SubscriptionId, Callback<T>)>>,
+}
+
+impl<T> Subscribers<T> {
+    pub const fn new() -> Self {
+        Subscribers {
+            next_id: AtomicU64::new(0),
+            callbacks: const_mutex(Vec::new()),
+        }
+    }
+
+    /// Runs every callback with the value that was replaced and the value that replaced it
+    pub fn notify(&self, old: &Arc<T>, new: &Arc<T>) {
+        NOTIFYING.with(|notifying| notifying.borrow_mut().push(self.address()));
+        let _notifying = Notifying(self.address());
+        self.callbacks
+            .lock()
+            .retain_mut(|(_, callback)| callback(old, new));
+    }
+
+    /// Panics if this thread is running one of these callbacks. Globals call this before taking
+    /// the lock that `notify` runs under, which would otherwise deadlock.
+    pub fn check_not_notifying(&self) {
+        if NOTIFYING.with(|notifying| notifying.borrow().contains(&self.address())) {
+            panic!(
+                "a subscriber callback can't publish to the global it was called for, \
+                 or subscribe to it or unsubscribe from it"
+            );
+        }
+    }
+
+    fn address(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn add(&self, callback: Callback<T>) -> SubscriptionId {
+        self.check_not_notifying();
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.callbacks.lock().push((id, callback));
+        id
+    }
+}
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Subscribers::new()
+    }
+}
+
+/// A global that can tell you when it changes
+pub trait Subscribe {
+    type Value: 'static;
+
+    fn subscribers(&self) -> &Subscribers<Self::Value>;
+
+    /// Calls `f` with the old and the new value every time a new value is published
+    fn subscribe(
+        &self,
+        mut f: impl FnMut(&Self::Value, &Self::Value) + Send + 'static,
+    ) -> SubscriptionId {
+        self.subscribers().add(Box::new(move |old, new| {
+            f(old, new);
+            true
+        }))
+    }
+
+    /// Like `subscribe`, but only for the part of the value returned by `project`, e.g. one field.
+    /// `f` isn't called when a new value is published with the same `project`ion.
+    fn subscribe_field<P: PartialEq>(
+        &self,
+        project: impl Fn(&Self::Value) -> P + Send + 'static,
+        mut f: impl FnMut(&P, &P) + Send + 'static,
+    ) -> SubscriptionId {
+        self.subscribers().add(Box::new(move |old, new| {
+            let (old, new) = (project(old), project(new));
+            if old != new {
+                f(&old, &new);
+            }
+            true
+        }))
+    }
+
+    /// Every change from now on, in the order they were published. The subscription ends when
+    /// the receiver is dropped.
+    fn changes(&self) -> Changes<Self::Value>
+    where
+        Self::Value: Send + Sync,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers().add(Box::new(move |old, new| {
+            let change = Change {
+                old: old.clone(),
+                new: new.clone(),
+            };
+            sender.send(change).is_ok()
+        }));
+        Changes { receiver }
+    }
+
+    /// Stops calling the subscription's callback. Returns false if it was already gone.
+    fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        self.subscribers().check_not_notifying();
+        let mut callbacks = self.subscribers().callbacks.lock();
+        let len = callbacks.len();
+        callbacks.retain(|(i, _)| *i != id);
+        callbacks.len() < len
+    }
+}
+
+/// A new value, along with the one it replaced
+#[derive(Debug)]
+pub struct Change<T> {
+    pub old: Arc<T>,
+    pub new: Arc<T>,
+}
+
+/// The changes to a global, from `Subscribe::changes`
+///
+/// Iterating blocks until the next change.
+pub struct Changes<T> {
+    receiver: Receiver<Change<T>>,
+}
+
+impl<T> Changes<T> {
+    /// The next change, if there is one already
+    pub fn try_next(&self) -> Option<Change<T>> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// The next change, waiting up to `timeout` for it
+    pub fn next_timeout(&self, timeout: Duration) -> Option<Change<T>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(change) => Some(change),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl<T> Iterator for Changes<T> {
+    type Item = Change<T>;
+
+    fn next(&mut self) -> Option<Change<T>> {
+        self.receiver.recv().ok()
+    }
+}
@@ -0,0 +1,36 @@
+//! "Did you mean ...?" suggestions for keys that aren't in a static map
+
+use crate::lookup::StrMap;
+
+/// Returns the keys of `map` that are close to `input`, closest first. Ties are broken
+/// alphabetically, so the result doesn't depend on the order of the map.
+///
+/// A key counts as close if its edit distance from `input` is at most a third of the length of
+/// `input` (but always allowing one typo). That's the same rule of thumb `rustc` uses.
+pub fn did_you_mean(map: &impl StrMap, input: &str) -> Vec<&'static str> {
+    let max_distance = std::cmp::max(1, input.chars().count() / 3);
+    let mut candidates: Vec<(usize, &'static str)> = map
+        .keys()
+        .map(|key| (edit_distance(input, key), key))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    candidates.sort();
+    candidates.into_iter().map(|(_, key)| key).collect()
+}
+
+/// The Levenshtein distance between `a` and `b`, counted in `char`s
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // Only the previous row of the usual table is needed to compute the next one
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + if ca == cb { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
@@ -0,0 +1,3 @@
+//! Typed tables that `build/tables.rs` generates from the CSV files in `data/`
+
+include!(concat!(env!("OUT_DIR"), "/tables.rs"));
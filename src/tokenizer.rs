@@ -0,0 +1,107 @@
+//! A small tokenizer that uses the generated `KEYWORDS` table to recognize keywords
+
+use crate::keywords::{Keyword2, KEYWORDS};
+
+/// A byte range into the tokenized source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword(Keyword2),
+    /// A word that isn't in `KEYWORDS`
+    Identifier,
+    /// Digits, optionally followed by a `.` and more digits
+    Number,
+    /// Any other character that isn't whitespace
+    Punct(char),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+impl Token {
+    /// The text of this token, given the source it came from
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span.start..self.span.end]
+    }
+}
+
+/// Splits `source` into tokens, skipping whitespace
+pub fn tokenize(source: &str) -> Tokenizer<'_> {
+    Tokenizer { source, pos: 0 }
+}
+
+/// An iterator over the tokens of a string, created by `tokenize`
+#[derive(Clone, Debug)]
+pub struct Tokenizer<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn eat_while(&mut self, f: impl Fn(char) -> bool) {
+        while let Some(c) = self.peek() {
+            if !f(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+}
+
+fn is_word_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_word_continue(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.eat_while(char::is_whitespace);
+        let start = self.pos;
+        let first = self.peek()?;
+        self.pos += first.len_utf8();
+
+        let kind = if is_word_start(first) {
+            self.eat_while(is_word_continue);
+            match KEYWORDS.get(&self.source[start..self.pos]) {
+                Some(&keyword) => TokenKind::Keyword(keyword),
+                None => TokenKind::Identifier,
+            }
+        } else if first.is_ascii_digit() {
+            self.eat_while(|c| c.is_ascii_digit());
+            // Only treat the `.` as a decimal point if a digit follows, so `1.foo` stays three tokens
+            let rest = &self.source[self.pos..];
+            if rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+                self.pos += 1;
+                self.eat_while(|c| c.is_ascii_digit());
+            }
+            TokenKind::Number
+        } else {
+            TokenKind::Punct(first)
+        };
+
+        Some(Token {
+            kind,
+            span: Span {
+                start,
+                end: self.pos,
+            },
+        })
+    }
+}
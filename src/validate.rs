@@ -0,0 +1,96 @@
+//! Validation that runs both in the build script and at run time. The build script includes
+//! this file with `#[path]`, so it can only depend on `std` and crates that are both normal and
+//! build dependencies.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A problem with a data file, with the position of the bad record or field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataError {
+    pub line: u64,
+    /// 1-based, like the line number
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "{}:{}: {}", self.line, column, self.message),
+            None => write!(f, "{}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+/// A 64-bit FNV-1a hash. It's not cryptographic, but it's easy to compute identically in the
+/// build script and at run time.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// A CSV record, along with where it came from
+pub struct Record {
+    record: csv::StringRecord,
+    line: u64,
+}
+
+impl Record {
+    /// Parses the field in the given column
+    pub fn field<T: FromStr>(&self, index: usize) -> Result<T, DataError> {
+        let field = &self.record[index];
+        field.parse().map_err(|_| self.error(index, format!("`{}` is not valid here", field)))
+    }
+
+    /// The line that the record starts on
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// An error about the field in the given column
+    pub fn error(&self, index: usize, message: String) -> DataError {
+        DataError {
+            line: self.line,
+            column: Some(index + 1),
+            message,
+        }
+    }
+}
+
+/// Reads a CSV document whose header has exactly `columns`, converting each record with `row`
+pub fn read_csv<T>(
+    text: &str,
+    columns: &[&str],
+    mut row: impl FnMut(&Record) -> Result<T, DataError>,
+) -> Result<Vec<T>, DataError> {
+    let csv_error = |e: csv::Error| DataError {
+        line: e.position().map_or(1, |p| p.line()),
+        column: None,
+        message: e.to_string(),
+    };
+
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let headers = reader.headers().map_err(csv_error)?;
+    if headers.iter().ne(columns.iter().copied()) {
+        return Err(DataError {
+            line: 1,
+            column: None,
+            message: format!("expected the columns {}", columns.join(",")),
+        });
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let line = record.position().map_or(0, |p| p.line());
+        rows.push(row(&Record { record, line })?);
+    }
+    Ok(rows)
+}
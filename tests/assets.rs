@@ -0,0 +1,38 @@
+use global_data_in_rust::assets::{Entry, ASSETS};
+use sha2::{Digest, Sha256};
+
+#[test]
+fn files_are_embedded_with_their_metadata() {
+    let css = ASSETS.get("css/site.css").unwrap();
+    assert!(css.starts_with(b"body {"));
+    let metadata = ASSETS.metadata("css/site.css").unwrap();
+    assert_eq!(metadata.size, css.len());
+    assert_eq!(metadata.sha256, <[u8; 32]>::from(Sha256::digest(&css)));
+    assert!(ASSETS.get("css").is_none());
+    assert!(ASSETS.get("missing.txt").is_none());
+}
+
+#[test]
+fn directories_can_be_listed() {
+    assert_eq!(
+        ASSETS.read_dir("templates/").unwrap(),
+        &[
+            Entry { path: "templates/about.html", is_dir: false },
+            Entry { path: "templates/index.html", is_dir: false },
+            Entry { path: "templates/partials", is_dir: true },
+        ]
+    );
+    assert_eq!(ASSETS.read_dir("").unwrap().len(), 2);
+    assert!(ASSETS.read_dir("css/site.css").is_none());
+}
+
+#[test]
+fn globs_match_sorted_paths() {
+    let top_level: Vec<_> = ASSETS.glob("templates/*.html").collect();
+    assert_eq!(top_level, vec!["templates/about.html", "templates/index.html"]);
+
+    let all_html: Vec<_> = ASSETS.glob("**/*.html").collect();
+    assert_eq!(all_html.len(), 3);
+    assert_eq!(ASSETS.glob("**").count(), ASSETS.paths().count());
+    assert_eq!(ASSETS.glob("css/sit?.css").count(), 1);
+}
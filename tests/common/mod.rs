@@ -0,0 +1,62 @@
+//! Helpers shared by the integration tests, which include them with `mod common;`
+
+// Each test file only uses some of them
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use global_data_in_rust::config::{ParseFn, ReloadableConfig, ValidateFn};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A file in the temp directory, which is deleted when this is dropped
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Creates the file with `text` in it. `name` is only for telling the files apart: tests run
+    /// in parallel, so the path also includes the process ID and a counter.
+    pub fn new(name: &str, text: &str) -> TempFile {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let file = TempFile {
+            path: env::temp_dir().join(format!("global-data-{}-{}-{}", process::id(), id, name)),
+        };
+        file.write(text);
+        file
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replaces the contents in one step, so a watcher never sees the file half written
+    pub fn write(&self, text: &str) {
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, text).unwrap();
+        fs::rename(&temp, &self.path).unwrap();
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // A test may have removed the file itself
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Opens `file` as a config that's never dropped, for `watch` and `reload_on_sighup`, which need a
+/// `'static` one. A `static` would outlive the file.
+pub fn leak_config<T>(
+    file: &TempFile,
+    parse: ParseFn<T>,
+    validate: ValidateFn<T>,
+) -> &'static ReloadableConfig<T> {
+    Box::leak(Box::new(
+        ReloadableConfig::open(file.path(), parse, validate).unwrap(),
+    ))
+}
@@ -0,0 +1,18 @@
+use global_data_in_rust::compressed::{Compressed, CorruptError, SAMPLE};
+use global_data_in_rust::SAMPLE_BYTES;
+
+#[test]
+fn decompresses_to_the_original_bytes() {
+    assert_eq!(SAMPLE.len(), SAMPLE_BYTES.len());
+    assert_eq!(&*SAMPLE, SAMPLE_BYTES);
+    // The second access reuses the decompressed data
+    assert!(std::ptr::eq(SAMPLE.get(), SAMPLE.get()));
+}
+
+#[test]
+fn corrupt_data_is_detected() {
+    static GARBAGE: Compressed = Compressed::new(b"not deflate at all", 5, 0);
+    static WRONG_HASH: Compressed = Compressed::new(&[], 0, 0);
+    assert_eq!(GARBAGE.try_get(), Err(CorruptError));
+    assert_eq!(WRONG_HASH.try_get(), Err(CorruptError));
+}
@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fs;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use global_data_in_rust::config::{ConfigError, ReloadableConfig};
+use global_data_in_rust::global::GlobalData;
+
+mod common;
+
+use common::{leak_config, TempFile};
+
+#[derive(Debug, PartialEq)]
+struct Settings {
+    max_players: u32,
+    tick_rate: u32,
+}
+
+/// Parses `max_players tick_rate`
+fn parse(text: &str) -> Result<Settings, Box<dyn Error + Send + Sync>> {
+    let mut numbers = text.split_whitespace().map(str::parse::<u32>);
+    match (numbers.next(), numbers.next(), numbers.next()) {
+        (Some(max_players), Some(tick_rate), None) => Ok(Settings {
+            max_players: max_players?,
+            tick_rate: tick_rate?,
+        }),
+        _ => Err("expected two numbers".into()),
+    }
+}
+
+fn validate(settings: &Settings) -> Result<(), String> {
+    if settings.max_players == 0 {
+        return Err("max_players has to be at least 1".to_string());
+    }
+    Ok(())
+}
+
+fn wait_for(mut done: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !done() {
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn the_watcher_publishes_valid_changes() {
+    let file = TempFile::new("valid", "8 60");
+    let config = leak_config(&file, parse, validate);
+    assert_eq!(config.load().max_players, 8);
+
+    let _watcher = config.watch(Duration::from_millis(5), |e| panic!("{}", e));
+    file.write("16 30");
+    wait_for(|| config.load().max_players == 16);
+    assert_eq!(
+        *GlobalData::read(config),
+        Settings {
+            max_players: 16,
+            tick_rate: 30
+        }
+    );
+}
+
+#[test]
+fn invalid_changes_are_reported_and_keep_the_previous_value() {
+    let file = TempFile::new("invalid", "8 60");
+    let config = leak_config(&file, parse, validate);
+    let (errors, received) = mpsc::channel();
+    let watcher = config.watch(Duration::from_millis(5), move |e| errors.send(e).unwrap());
+
+    file.write("8 sixty");
+    let error = received.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(error, ConfigError::Parse(..)), "{}", error);
+    file.write("0 60");
+    let error = received.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(error
+        .to_string()
+        .ends_with("invalid config: max_players has to be at least 1"));
+    assert_eq!(config.load().max_players, 8);
+
+    // Each bad version is only reported once
+    drop(watcher);
+    assert!(received.try_recv().is_err());
+    assert!(!config.poll().unwrap());
+}
+
+#[test]
+fn reload_rereads_the_file_on_demand() {
+    let file = TempFile::new("reload", "8 60");
+    let config = ReloadableConfig::open(file.path(), parse, validate).unwrap();
+    assert!(!config.poll().unwrap());
+    file.write("9 60");
+    config.reload().unwrap();
+    assert_eq!(config.load().max_players, 9);
+
+    fs::remove_file(file.path()).unwrap();
+    assert!(matches!(config.reload(), Err(ConfigError::Io(..))));
+    assert_eq!(config.load().max_players, 9);
+    assert!(ReloadableConfig::open(file.path(), parse, validate).is_err());
+}
@@ -0,0 +1,274 @@
+//! Runs the same scenarios against every `GlobalData` backend
+//!
+//! Each backend is declared once, in the `conformance!` invocation at the bottom, as a function
+//! that returns a fresh `'static` global. Every scenario gets its own copy, so they don't interfere.
+//! The global's initial value should come from calling `init`, if the backend has an initializer.
+//! Read-only backends are expected to reject writes, so they go through the same scenarios.
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Barrier;
+use std::thread;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use global_data_in_rust::config::ReloadableConfig;
+use global_data_in_rust::global::{Const, GlobalData, LazyMutex, Reloadable, SetError};
+use global_data_in_rust::group::Group;
+use lazy_static::lazy_static;
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::{const_mutex, const_rwlock};
+
+mod common;
+
+use common::TempFile;
+
+const THREADS: usize = 8;
+const READS: usize = 1000;
+
+/// A value that shows whether it was written in one piece
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Value {
+    generation: u64,
+    check: u64,
+}
+
+impl Value {
+    const fn new(generation: u64) -> Value {
+        Value {
+            generation,
+            check: !generation,
+        }
+    }
+
+    fn is_consistent(&self) -> bool {
+        self.check == !self.generation
+    }
+}
+
+/// The initializer for one scenario's global
+struct Init {
+    panic_first: bool,
+    calls: AtomicUsize,
+}
+
+impl Init {
+    const fn new(panic_first: bool) -> Init {
+        Init {
+            panic_first,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    fn run(&self) -> Value {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        // Give racing threads a chance to pile up behind the first one
+        thread::sleep(Duration::from_millis(10));
+        if self.panic_first && call == 0 {
+            panic!("the initializer failed");
+        }
+        Value::new(0)
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+/// Runs `f` on `THREADS` threads at once
+fn on_threads(f: impl Fn(usize) + Sync) {
+    let barrier = Barrier::new(THREADS);
+    thread::scope(|scope| {
+        for i in 0..THREADS {
+            let (barrier, f) = (&barrier, &f);
+            scope.spawn(move || {
+                barrier.wait();
+                f(i)
+            });
+        }
+    });
+}
+
+mod scenarios {
+    use super::*;
+
+    pub fn concurrent_readers<G: GlobalData<Value = Value> + Sync>(
+        global: fn() -> &'static G,
+        _: &Init,
+    ) {
+        on_threads(|_| {
+            for _ in 0..READS {
+                assert_eq!(*global().read(), Value::new(0));
+            }
+        });
+    }
+
+    pub fn writer_races_readers<G: GlobalData<Value = Value> + Sync>(
+        global: fn() -> &'static G,
+        _: &Init,
+    ) {
+        if global().try_set(Value::new(1)).is_err() {
+            return;
+        }
+        on_threads(|i| {
+            if i == 0 {
+                for generation in 2..=READS as u64 {
+                    global().try_set(Value::new(generation)).unwrap();
+                }
+            } else {
+                let mut last = 0;
+                for _ in 0..READS {
+                    let value = *global().read();
+                    assert!(value.is_consistent(), "torn read: {:?}", value);
+                    assert!(value.generation >= last, "went back in time: {:?}", value);
+                    last = value.generation;
+                }
+            }
+        });
+        assert_eq!(*global().read(), Value::new(READS as u64));
+    }
+
+    pub fn init_runs_once<G: GlobalData<Value = Value> + Sync>(
+        global: fn() -> &'static G,
+        init: &Init,
+    ) {
+        on_threads(|_| assert_eq!(*global().read(), Value::new(0)));
+        assert!(init.calls() <= 1, "initialized {} times", init.calls());
+    }
+
+    /// The panic reaches the reader, and nobody ever sees a half-initialized value. Later reads may
+    /// retry the initializer or keep panicking, depending on the backend.
+    pub fn panic_during_init<G: GlobalData<Value = Value> + Sync>(
+        global: fn() -> &'static G,
+        init: &Init,
+    ) {
+        let first = panic::catch_unwind(|| *global().read());
+        if init.calls() == 0 {
+            assert_eq!(first.unwrap(), Value::new(0));
+            return;
+        }
+        assert!(first.is_err(), "the initializer's panic was swallowed");
+
+        for _ in 0..2 {
+            if let Ok(value) = panic::catch_unwind(|| *global().read()) {
+                assert_eq!(value, Value::new(0));
+            }
+        }
+    }
+
+    pub fn updates_are_visible<G: GlobalData<Value = Value> + Sync>(
+        global: fn() -> &'static G,
+        _: &Init,
+    ) {
+        let before = *global().read();
+        let result = thread::spawn(move || global().try_set(Value::new(7)))
+            .join()
+            .unwrap();
+        let expected = match result {
+            Ok(()) => Value::new(7),
+            Err(e @ SetError::ReadOnly(_)) | Err(e @ SetError::AlreadySet(_)) => {
+                assert_eq!(e.into_inner(), Value::new(7));
+                before
+            }
+        };
+        assert_eq!(*global().read(), expected);
+        on_threads(|_| assert_eq!(*global().read(), expected));
+    }
+}
+
+macro_rules! conformance {
+    ($($backend:ident: |$init:ident| $body:block)*) => {$(
+        mod $backend {
+            use super::*;
+
+            conformance!(@test concurrent_readers, false, |$init| $body);
+            conformance!(@test writer_races_readers, false, |$init| $body);
+            conformance!(@test init_runs_once, false, |$init| $body);
+            conformance!(@test panic_during_init, true, |$init| $body);
+            conformance!(@test updates_are_visible, false, |$init| $body);
+        }
+    )*};
+    (@test $scenario:ident, $panic_first:expr, |$init:ident| $body:block) => {
+        #[test]
+        fn $scenario() {
+            static INIT: Init = Init::new($panic_first);
+
+            #[allow(dead_code)]
+            fn $init() -> Value {
+                INIT.run()
+            }
+
+            fn global() -> &'static (impl GlobalData<Value = Value> + Sync) $body
+
+            scenarios::$scenario(global, &INIT);
+        }
+    };
+}
+
+// The generated maps aren't here: their value is the map itself, which is fixed at compile time. The
+// expansions of `global!` are all among these.
+conformance! {
+    constant: |init| {
+        static GLOBAL: Const<Value> = Const(Value::new(0));
+        &GLOBAL
+    }
+
+    lazy_static_ref: |init| {
+        lazy_static! {
+            static ref GLOBAL: Value = init();
+        }
+        global_data_in_rust::lazy_static_global_data!(GLOBAL: Value);
+        &GLOBAL
+    }
+
+    once_cell_lazy: |init| {
+        static GLOBAL: Lazy<Value> = Lazy::new(init);
+        &GLOBAL
+    }
+
+    once_cell_once: |init| {
+        static GLOBAL: OnceCell<Value> = OnceCell::new();
+        GLOBAL.get_or_init(init);
+        &GLOBAL
+    }
+
+    mutex: |init| {
+        static GLOBAL: parking_lot::Mutex<Value> = const_mutex(Value::new(0));
+        &GLOBAL
+    }
+
+    rw_lock: |init| {
+        static GLOBAL: parking_lot::RwLock<Value> = const_rwlock(Value::new(0));
+        &GLOBAL
+    }
+
+    lazy_mutex: |init| {
+        static GLOBAL: LazyMutex<Value> = LazyMutex::new(init);
+        &GLOBAL
+    }
+
+    arc_swap_lazy: |init| {
+        static GLOBAL: Lazy<ArcSwap<Value>> = Lazy::new(|| ArcSwap::from_pointee(init()));
+        &*GLOBAL
+    }
+
+    reloadable: |init| {
+        static GLOBAL: Reloadable<Value> = Reloadable::new(|| Ok(init()));
+        &GLOBAL
+    }
+
+    reloadable_config: |init| {
+        // The value comes from `init` rather than the file, which only has to exist while it's
+        // opened
+        static GLOBAL: Lazy<ReloadableConfig<Value>> = Lazy::new(|| {
+            let file = TempFile::new("conformance", "");
+            ReloadableConfig::open(file.path(), |_| Ok(init()), |_| Ok(())).unwrap()
+        });
+        &*GLOBAL
+    }
+
+    group: |init| {
+        static GLOBAL: Lazy<Group<Value>> = Lazy::new(|| Group::new(init()));
+        &*GLOBAL
+    }
+}
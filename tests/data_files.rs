@@ -0,0 +1,165 @@
+use global_data_in_rust::data_files::{read_keywords, Column, Enums, Ids, Rows, Type};
+use global_data_in_rust::validate::DataError;
+
+const AMMO: &[Column] = &[
+    Column {
+        name: "name",
+        ty: Type::Str,
+    },
+    Column {
+        name: "damage_bonus",
+        ty: Type::U16,
+    },
+];
+
+const WEAPONS: &[Column] = &[
+    Column {
+        name: "name",
+        ty: Type::Str,
+    },
+    Column {
+        name: "damage",
+        ty: Type::U16,
+    },
+    Column {
+        name: "ammo",
+        ty: Type::OptionalRef("Ammo"),
+    },
+];
+
+const LOOT: &[Column] = &[
+    Column {
+        name: "zone",
+        ty: Type::U16,
+    },
+    Column {
+        name: "tier",
+        ty: Type::U8,
+    },
+];
+
+fn error(line: u64, column: Option<usize>, message: &str) -> DataError {
+    DataError {
+        line,
+        column,
+        message: message.to_string(),
+    }
+}
+
+fn ammo_ids() -> Ids<'static> {
+    let ammo = vec![("arrow".to_string(), 0), ("bolt".to_string(), 1)];
+    vec![("Ammo", ammo.into_iter().collect())]
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn a_bad_field_is_reported_at_its_line_and_column_in_the_file() {
+    // The columns don't have to be in the declared order, and errors point at the file's order
+    let text = "damage,name,ammo\n12,dagger,\nlots,bow,arrow\n";
+    let rows = Rows::read(text, WEAPONS).unwrap();
+    assert_eq!(
+        rows.literals(WEAPONS, &ammo_ids(), &Enums::new())
+            .unwrap_err(),
+        error(3, Some(1), "column `damage`: `lots` is not a valid u16")
+    );
+
+    let text = "name,damage,ammo\ndagger,70000,\n";
+    let rows = Rows::read(text, WEAPONS).unwrap();
+    assert_eq!(
+        rows.literals(WEAPONS, &ammo_ids(), &Enums::new())
+            .unwrap_err()
+            .to_string(),
+        "2:2: column `damage`: `70000` is not a valid u16"
+    );
+}
+
+#[test]
+fn a_reference_to_a_missing_row_is_reported() {
+    let text = "name,damage,ammo\ndagger,12,\nbow,8,arrow\ncrossbow,10,quarrel\n";
+    let rows = Rows::read(text, WEAPONS).unwrap();
+    assert_eq!(
+        rows.literals(WEAPONS, &ammo_ids(), &Enums::new())
+            .unwrap_err(),
+        error(4, Some(3), "column `ammo`: there is no Ammo `quarrel`")
+    );
+
+    let text = "name,damage,ammo\ndagger,12,\nbow,8,arrow\n";
+    let literals = Rows::read(text, WEAPONS)
+        .unwrap()
+        .literals(WEAPONS, &ammo_ids(), &Enums::new())
+        .unwrap();
+    assert_eq!(literals[0][2], "None");
+    assert_eq!(literals[1][2], "Some(AmmoId(0))");
+}
+
+#[test]
+fn duplicate_keys_are_reported_with_the_first_definition() {
+    let text = "name,damage_bonus\narrow,1\nbolt,2\narrow,3\n";
+    let rows = Rows::read(text, AMMO).unwrap();
+    assert_eq!(
+        rows.check_keys(AMMO, &[0], None).unwrap_err(),
+        error(4, None, "duplicate name `arrow` (first defined on line 2)")
+    );
+
+    // Packed keys are compared as numbers, so `07` is the same zone as `7`
+    let text = "zone,tier\n7,1\n7,2\n07,1\n";
+    let rows = Rows::read(text, LOOT).unwrap();
+    let packed = rows
+        .pack_keys(LOOT, &[0, 1], &[16, 8], &Enums::new())
+        .unwrap();
+    assert_eq!(
+        rows.check_keys(LOOT, &[0, 1], Some(&packed)).unwrap_err(),
+        error(
+            4,
+            None,
+            "duplicate zone and tier `07,1` (first defined on line 2)"
+        )
+    );
+}
+
+#[test]
+fn key_columns_that_dont_parse_are_reported_before_packing() {
+    let text = "tier,zone\n1,7\n2,seven\n";
+    let rows = Rows::read(text, LOOT).unwrap();
+    assert_eq!(
+        rows.pack_keys(LOOT, &[0, 1], &[16, 8], &Enums::new())
+            .unwrap_err(),
+        error(3, Some(2), "column `zone`: `seven` is not a valid u16")
+    );
+}
+
+#[test]
+fn the_header_has_to_match_the_declared_columns() {
+    assert_eq!(
+        Rows::read("name\narrow\n", AMMO).err(),
+        Some(error(1, None, "missing column `damage_bonus`"))
+    );
+    assert_eq!(
+        Rows::read("name,damage_bonus,weight\narrow,1,0.1\n", AMMO).err(),
+        Some(error(1, Some(3), "undeclared column `weight`"))
+    );
+}
+
+#[test]
+fn keyword_errors_point_at_the_bad_field() {
+    let text = "keyword,variant\nloop,Loop\nbreak,Break\nloop,Loop\n";
+    assert_eq!(
+        read_keywords(text).err(),
+        Some(error(
+            4,
+            Some(1),
+            "duplicate keyword `loop` (first defined on line 2)"
+        ))
+    );
+
+    let text = "keyword,variant\nloop,Loop\nfn,fn\n";
+    assert_eq!(
+        read_keywords(text).err(),
+        Some(error(
+            3,
+            Some(2),
+            "`fn` is not a valid variant name for keyword `fn`"
+        ))
+    );
+}
@@ -0,0 +1,90 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use arc_swap::ArcSwap;
+use global_data_in_rust::global::{Const, GlobalData, ReloadError, Reloadable, SetError};
+use global_data_in_rust::keywords::{Keyword2, KEYWORDS};
+use lazy_static::lazy_static;
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::{const_mutex, const_rwlock, Mutex, RwLock};
+
+fn double<G: GlobalData<Value = u32>>(global: &'static G) -> u32 {
+    *global.read() * 2
+}
+
+lazy_static! {
+    static ref LAZY_STATIC: u32 = 21;
+}
+global_data_in_rust::lazy_static_global_data!(LAZY_STATIC: u32);
+
+#[test]
+fn every_backend_can_be_read_through_the_same_call() {
+    static CONST: Const<u32> = Const(21);
+    static LAZY: Lazy<u32> = Lazy::new(|| 21);
+    static ONCE: OnceCell<u32> = OnceCell::new();
+    static MUTEX: Mutex<u32> = const_mutex(21);
+    static RW_LOCK: RwLock<u32> = const_rwlock(21);
+    static ARC_SWAP: Lazy<ArcSwap<u32>> = Lazy::new(|| ArcSwap::from_pointee(21));
+    static RELOADABLE: Reloadable<u32> = Reloadable::new(|| Ok(21));
+    ONCE.try_set(21).unwrap();
+
+    assert_eq!(double(&CONST), 42);
+    assert_eq!(double(&LAZY_STATIC), 42);
+    assert_eq!(double(&LAZY), 42);
+    assert_eq!(double(&ONCE), 42);
+    assert_eq!(double(&MUTEX), 42);
+    assert_eq!(double(&RW_LOCK), 42);
+    assert_eq!(double(&*ARC_SWAP), 42);
+    assert_eq!(double(&RELOADABLE), 42);
+    assert_eq!(KEYWORDS.read().get("loop"), Some(&Keyword2::Loop));
+}
+
+#[test]
+fn read_only_backends_hand_back_the_rejected_value() {
+    static CONST: Const<&str> = Const("fixed");
+    static ONCE: OnceCell<String> = OnceCell::new();
+
+    assert_eq!(CONST.try_set("changed"), Err(SetError::ReadOnly("changed")));
+    assert_eq!(CONST.get(), "fixed");
+    assert!(matches!(CONST.reload(), Err(ReloadError::Unsupported)));
+
+    ONCE.try_set("first".to_string()).unwrap();
+    let rejected = ONCE.try_set("second".to_string()).unwrap_err();
+    assert_eq!(rejected.into_inner(), "second");
+    assert_eq!(GlobalData::get(&ONCE), "first");
+}
+
+#[test]
+fn writable_backends_see_the_new_value() {
+    static MUTEX: Mutex<Vec<u32>> = const_mutex(Vec::new());
+    static ARC_SWAP: Lazy<ArcSwap<Vec<u32>>> = Lazy::new(|| ArcSwap::from_pointee(Vec::new()));
+
+    let arc_swap: &'static ArcSwap<Vec<u32>> = &ARC_SWAP;
+
+    let before = arc_swap.read();
+    MUTEX.try_set(vec![1, 2]).unwrap();
+    arc_swap.try_set(vec![1, 2]).unwrap();
+
+    assert_eq!(MUTEX.get(), [1, 2]);
+    assert_eq!(arc_swap.get(), [1, 2]);
+    assert!(before.is_empty());
+}
+
+#[test]
+fn reloading_reruns_the_loader_and_keeps_the_old_value_on_failure() {
+    static LOADS: AtomicU32 = AtomicU32::new(0);
+    static COUNTER: Reloadable<u32> =
+        Reloadable::new(|| match LOADS.fetch_add(1, Ordering::SeqCst) {
+            2 => Err("disk on fire".into()),
+            n => Ok(n),
+        });
+
+    assert_eq!(COUNTER.get(), 0);
+    COUNTER.reload().unwrap();
+    assert_eq!(COUNTER.get(), 1);
+
+    match COUNTER.reload() {
+        Err(ReloadError::Failed(e)) => assert_eq!(e.to_string(), "disk on fire"),
+        other => panic!("expected a failed reload, got {:?}", other),
+    }
+    assert_eq!(COUNTER.get(), 1);
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use global_data_in_rust::global;
+use global_data_in_rust::global::{Const, GlobalData, LazyMutex, Reloadable, SetError};
+
+static RELOADS: AtomicU32 = AtomicU32::new(0);
+
+global! {
+    /// Compile time and immutable
+    static MAX_PLAYERS: u32 = 8;
+
+    #[global(mutable, no_heap)]
+    static SCORE: u32 = 0;
+
+    #[global(runtime)]
+    pub static GREETING: String = "hello".to_uppercase();
+
+    #[global(runtime, mutable)]
+    static SESSIONS: HashMap<u32, &'static str> = HashMap::new();
+
+    #[global(runtime, reloadable)]
+    static GENERATION: u32 = RELOADS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn each_combination_expands_to_its_primitive() {
+    let _: &Const<u32> = &MAX_PLAYERS;
+    let _: &parking_lot::Mutex<u32> = &SCORE;
+    let _: &once_cell::sync::Lazy<String> = &GREETING;
+    let _: &LazyMutex<HashMap<u32, &str>> = &SESSIONS;
+    let _: &Reloadable<u32> = &GENERATION;
+}
+
+#[test]
+fn the_expansions_behave_like_their_primitives() {
+    assert_eq!(MAX_PLAYERS.get(), 8);
+    assert_eq!(MAX_PLAYERS.try_set(16), Err(SetError::ReadOnly(16)));
+
+    SCORE.try_set(10).unwrap();
+    *SCORE.lock() += 1;
+    assert_eq!(SCORE.get(), 11);
+
+    assert_eq!(GREETING.get(), "HELLO");
+
+    SESSIONS.lock().insert(1, "paul");
+    assert_eq!(SESSIONS.read().get(&1), Some(&"paul"));
+
+    let first = GENERATION.get();
+    GENERATION.reload().unwrap();
+    assert_eq!(GENERATION.get(), first + 1);
+}
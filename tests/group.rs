@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use global_data_in_rust::group::Group;
+use global_data_in_rust::subscribe::Subscribe;
+use once_cell::sync::Lazy;
+
+/// Three globals that are always updated together, each holding the generation that wrote it
+#[derive(Clone)]
+struct Globals {
+    routes: Arc<Vec<u64>>,
+    flags: Arc<u64>,
+    limits: Arc<(u64, u64)>,
+}
+
+impl Globals {
+    fn new(generation: u64) -> Globals {
+        Globals {
+            routes: Arc::new(vec![generation; 16]),
+            flags: Arc::new(generation),
+            limits: Arc::new((generation, generation)),
+        }
+    }
+}
+
+#[test]
+fn snapshots_never_see_half_a_commit() {
+    static GROUP: Lazy<Group<Globals>> = Lazy::new(|| Group::new(Globals::new(0)));
+    static DONE: AtomicBool = AtomicBool::new(false);
+    static START: Lazy<Barrier> = Lazy::new(|| Barrier::new(5));
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                START.wait();
+                while !DONE.load(Ordering::Relaxed) {
+                    let snapshot = GROUP.snapshot();
+                    let generation = snapshot.generation;
+                    assert!(snapshot.routes.iter().all(|&g| g == generation));
+                    assert_eq!(*snapshot.flags, generation);
+                    assert_eq!(*snapshot.limits, (generation, generation));
+                }
+            })
+        })
+        .collect();
+
+    START.wait();
+    for generation in 1..=1000 {
+        // Each global is replaced by its own statement, as separate stores would be
+        let committed = GROUP.commit(|globals| {
+            globals.routes = Arc::new(vec![generation; 16]);
+            globals.flags = Arc::new(generation);
+            globals.limits = Arc::new((generation, generation));
+        });
+        assert_eq!(committed, generation);
+    }
+    DONE.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+}
+
+#[test]
+fn a_failed_commit_publishes_nothing() {
+    let group = Group::new(Globals::new(0));
+    let changes = group.changes();
+
+    let result = group.try_commit(|globals| {
+        globals.flags = Arc::new(1);
+        Err("limits are out of range")
+    });
+    assert_eq!(result, Err("limits are out of range"));
+    let snapshot = group.snapshot();
+    assert_eq!((snapshot.generation, *snapshot.flags), (0, 0));
+    assert!(changes.try_next().is_none());
+
+    // Globals that the commit doesn't touch are shared with the previous version
+    group.commit(|globals| globals.flags = Arc::new(1));
+    let change = changes.try_next().unwrap();
+    assert_eq!((*change.old.flags, *change.new.flags), (0, 1));
+    assert!(Arc::ptr_eq(&change.old.routes, &change.new.routes));
+}
+
+#[test]
+fn rollback_restores_every_global_at_once() {
+    let group = Group::new(Globals::new(0)).keep_history(2);
+    group.commit(|globals| *globals = Globals::new(1));
+    group.commit(|globals| *globals = Globals::new(2));
+
+    group.rollback(2).unwrap();
+    let snapshot = group.snapshot();
+    assert_eq!(snapshot.generation, 3);
+    assert_eq!(snapshot.source, "rollback to generation 0");
+    assert_eq!((*snapshot.flags, *snapshot.limits), (0, (0, 0)));
+    assert!(group.rollback(3).is_err());
+}
@@ -0,0 +1,76 @@
+use global_data_in_rust::config::ReloadableConfig;
+use global_data_in_rust::global::{GlobalData, Reloadable};
+use global_data_in_rust::history::RollbackError;
+use global_data_in_rust::subscribe::Subscribe;
+
+mod common;
+
+use common::TempFile;
+
+#[test]
+fn each_load_comes_with_its_generation() {
+    static GLOBAL: Reloadable<&str> = Reloadable::new(|| Ok("first"));
+    let version = GLOBAL.load();
+    assert_eq!((version.generation, *version.value), (0, "first"));
+    assert_eq!(version.source, "initial load");
+
+    GLOBAL.try_set("second").unwrap();
+    GLOBAL.reload().unwrap();
+    let version = GLOBAL.load();
+    assert_eq!((version.generation, *version.value), (2, "first"));
+    assert_eq!(version.source, "reload");
+    assert!(version.published >= GLOBAL.history()[1].published);
+}
+
+#[test]
+fn rollback_publishes_an_old_value_as_a_new_generation() {
+    static GLOBAL: Reloadable<u32> = Reloadable::new(|| Ok(1));
+    GLOBAL.load();
+    let changes = GLOBAL.changes();
+    for value in 2..=4 {
+        GLOBAL.try_set(value).unwrap();
+    }
+    assert_eq!(std::iter::from_fn(|| changes.try_next()).count(), 3);
+
+    GLOBAL.rollback(2).unwrap();
+    let version = GLOBAL.load();
+    assert_eq!((version.generation, *version.value), (4, 2));
+    assert_eq!(version.source, "rollback to generation 1");
+    assert_eq!(*changes.try_next().unwrap().old, 4);
+
+    let history: Vec<(u64, u32)> = GLOBAL
+        .history()
+        .iter()
+        .map(|version| (version.generation, *version.value))
+        .collect();
+    assert_eq!(history, [(4, 2), (3, 4), (2, 3), (1, 2), (0, 1)]);
+    assert_eq!(
+        GLOBAL.rollback(5),
+        Err(RollbackError {
+            requested: 5,
+            available: 4
+        })
+    );
+    assert!(GLOBAL.rollback(0).is_err());
+}
+
+#[test]
+fn history_is_bounded() {
+    static GLOBAL: Reloadable<u32> = Reloadable::new(|| Ok(0)).keep_history(2);
+    GLOBAL.load();
+    for value in 1..=10 {
+        GLOBAL.try_set(value).unwrap();
+    }
+    let generations: Vec<u64> = GLOBAL.history().iter().map(|v| v.generation).collect();
+    assert_eq!(generations, [10, 9, 8]);
+
+    let file = TempFile::new("history", "1");
+    let config = ReloadableConfig::open(file.path(), |text| Ok(text.parse::<u32>()?), |_| Ok(()))
+        .unwrap()
+        .keep_history(0);
+    file.write("2");
+    config.reload().unwrap();
+    assert_eq!(config.history().len(), 1);
+    assert!(config.load().source.starts_with("reloaded "));
+    assert!(config.rollback(1).is_err());
+}
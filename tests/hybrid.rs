@@ -0,0 +1,44 @@
+use global_data_in_rust::difficulty::{self, Difficulty};
+use global_data_in_rust::hybrid::{Hybrid, LoadError, Source, DIFFICULTIES, DIFFICULTIES_HASH};
+
+mod common;
+
+use common::TempFile;
+
+#[test]
+fn the_shipped_file_matches_the_build_time_hash() {
+    let difficulties = DIFFICULTIES.load().unwrap();
+    assert_eq!(DIFFICULTIES.source(), Some(Source::Validated));
+    assert_eq!(difficulties[1].name, "normal");
+    assert!(std::ptr::eq(DIFFICULTIES.get(), difficulties));
+}
+
+#[test]
+fn an_edited_file_is_revalidated() {
+    static EDITED: Hybrid<Vec<Difficulty>> =
+        Hybrid::new("unused", DIFFICULTIES_HASH, difficulty::parse);
+    let file = TempFile::new(
+        "edited.csv",
+        "name,enemy_health,enemy_damage,lives\nzen,0.1,0.1,9\n",
+    );
+
+    let difficulties = EDITED.load_from(file.path()).unwrap();
+    assert_eq!(EDITED.source(), Some(Source::Revalidated));
+    assert_eq!(difficulties[0].lives, 9);
+}
+
+#[test]
+fn an_invalid_edit_is_reported_with_its_position() {
+    static INVALID: Hybrid<Vec<Difficulty>> =
+        Hybrid::new("unused", DIFFICULTIES_HASH, difficulty::parse);
+    let file = TempFile::new(
+        "invalid.csv",
+        "name,enemy_health,enemy_damage,lives\nzen,0.1,0.1,0\n",
+    );
+
+    match INVALID.load_from(file.path()) {
+        Err(LoadError::Invalid(_, e)) => assert_eq!((e.line, e.column), (2, Some(4))),
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+    assert_eq!(INVALID.source(), None);
+}
@@ -0,0 +1,64 @@
+use global_data_in_rust::include_data;
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    title: &'static str,
+    max_players: u8,
+    tick_rate: f32,
+    server: Server,
+    admins: &'static [Admin],
+}
+
+#[derive(Debug, PartialEq)]
+struct Server {
+    host: &'static str,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq)]
+struct Admin {
+    name: &'static str,
+    level: u8,
+}
+
+static CONFIG: Config = include_data!("data/config.toml" as Config {
+    server: Server,
+    admins: [Admin],
+});
+
+const ADMINS: &[Admin] = include_data!("tests/admins.json" as [Admin]);
+
+#[test]
+fn toml_is_parsed_at_compile_time() {
+    assert_eq!(CONFIG.title, "Global Data");
+    assert_eq!(CONFIG.max_players, 8);
+    assert_eq!(CONFIG.server.port, 7777);
+    assert_eq!(CONFIG.admins[1], Admin { name: "ferris", level: 1 });
+}
+
+#[test]
+fn json_is_parsed_at_compile_time() {
+    assert_eq!(ADMINS, CONFIG.admins);
+}
+
+#[derive(Debug, PartialEq)]
+struct Rates {
+    rate: f32,
+    samples: &'static [f64],
+}
+
+const RATES: Rates = include_data!("tests/rates.json" as Rates {
+    rate: f32,
+    samples: [f64],
+});
+
+#[test]
+fn integers_fill_fields_listed_as_floats() {
+    assert_eq!(
+        RATES,
+        Rates {
+            rate: 60.0,
+            samples: &[1.0, 2.5],
+        }
+    );
+}
@@ -0,0 +1,30 @@
+use global_data_in_rust::integrity::{self, hex, Blob, BlobRef, SampleTxt};
+use global_data_in_rust::SAMPLE_BYTES;
+
+#[test]
+fn every_embedded_blob_matches_its_build_time_hash() {
+    assert_eq!(integrity::verify_all(), Ok(()));
+    // sample.txt, its compressed copy, and the assets
+    assert!(integrity::blobs().len() > 2);
+}
+
+#[test]
+fn blobs_have_their_length_and_hash_as_consts() {
+    assert_eq!(SampleTxt::LEN, SAMPLE_BYTES.len());
+    assert_eq!(
+        hex(&SampleTxt::SHA256),
+        "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f"
+    );
+    assert_eq!(SampleTxt::blob_ref().version(), "dffd6021bb2bd5b0");
+}
+
+#[test]
+fn tampered_bytes_are_detected() {
+    let tampered = BlobRef {
+        bytes: b"Hello, Wordl!",
+        ..SampleTxt::blob_ref()
+    };
+    let error = tampered.verify().unwrap_err();
+    assert_eq!(error.name, "sample.txt");
+    assert_eq!(error.expected, SampleTxt::SHA256);
+}
@@ -0,0 +1,48 @@
+use global_data_in_rust::keywords::Keyword2;
+use global_data_in_rust::tables::{
+    KeywordDoc, Level, Loot, Weapon, Zone, KEYWORD_DOCS_BY_KEYWORD, LEVELS, LOOT_BY_ZONE_TIER,
+    RESERVED_KEYWORDS, ZONES_BY_NAME, ZONE_IDS,
+};
+
+#[test]
+fn integer_pair_and_enum_keys() {
+    assert_eq!(Level::get(3).unwrap().title, "journeyman");
+    assert_eq!(Level::get(0), None);
+    assert_eq!(Level::get(LEVELS.len() as u8).unwrap().experience, 3100);
+
+    let loot = Loot::get(30, 3).unwrap();
+    assert_eq!(loot.weapon, Weapon::id("greatsword").unwrap());
+    assert_eq!(loot.chance, 0.05);
+    // Neither half of the key is enough on its own
+    assert_eq!(Loot::get(30, 2), None);
+    assert_eq!(Loot::get(10, 3), None);
+    assert!(LOOT_BY_ZONE_TIER.contains_key((40, 3)));
+
+    assert_eq!(
+        KeywordDoc::get(Keyword2::Break).unwrap().summary,
+        "Exits a loop"
+    );
+    assert_eq!(KEYWORD_DOCS_BY_KEYWORD.len(), 5);
+}
+
+#[test]
+fn ordered_and_key_maps_iterate_in_file_order() {
+    let zones: Vec<&str> = ZONES_BY_NAME.keys().copied().collect();
+    assert_eq!(zones, ["meadow", "forest", "swamp", "caves", "keep"]);
+    assert_eq!(Zone::get("caves").unwrap().min_level, 4);
+
+    let keywords: Vec<Keyword2> = KEYWORD_DOCS_BY_KEYWORD.keys().copied().collect();
+    assert_eq!(keywords[0], Keyword2::Loop);
+    assert_eq!(keywords[4], Keyword2::Extern);
+    let tiers: Vec<(u16, u8)> = LOOT_BY_ZONE_TIER.keys().copied().collect();
+    assert_eq!(tiers[..3], [(10, 1), (10, 2), (20, 1)]);
+}
+
+#[test]
+fn sets_contain_every_value_of_their_column() {
+    assert!(RESERVED_KEYWORDS.contains("yield"));
+    assert!(!RESERVED_KEYWORDS.contains("loop"));
+    assert_eq!(RESERVED_KEYWORDS.len(), 12);
+    assert!(ZONE_IDS.contains(&25));
+    assert!(!ZONE_IDS.contains(&15));
+}
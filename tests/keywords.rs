@@ -0,0 +1,34 @@
+use global_data_in_rust::keywords::{get_normalized, suggest, Keyword2, KEYWORDS};
+
+#[test]
+fn every_variant_round_trips_through_its_text() {
+    assert_eq!(Keyword2::ALL.len(), Keyword2::count());
+    for &keyword in Keyword2::ALL {
+        assert_eq!(KEYWORDS.get(keyword.as_str()), Some(&keyword));
+        assert_eq!(keyword.to_string().parse(), Ok(keyword));
+        let name = format!("{:?}", keyword);
+        assert_eq!(Keyword2::from_variant_name(&name), Some(keyword));
+    }
+    assert_eq!(Keyword2::from_variant_name("loop"), None);
+}
+
+#[test]
+fn unknown_text_is_not_a_keyword() {
+    assert!("lop".parse::<Keyword2>().is_err());
+}
+
+#[test]
+fn normalized_lookup_ignores_case_and_whitespace() {
+    assert_eq!(get_normalized("LOOP"), Some(Keyword2::Loop));
+    assert_eq!(get_normalized(" loop "), Some(Keyword2::Loop));
+    assert_eq!(get_normalized("\tConTinue\n"), Some(Keyword2::Continue));
+    assert_eq!(get_normalized("lo op"), None);
+    assert_eq!(get_normalized("a much longer string than any keyword"), None);
+}
+
+#[test]
+fn misspelled_keywords_get_suggestions() {
+    assert_eq!(suggest("contine"), vec!["continue"]);
+    assert_eq!(suggest("lopp"), vec!["loop"]);
+    assert!(suggest("banana").is_empty());
+}
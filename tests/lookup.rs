@@ -0,0 +1,67 @@
+use global_data_in_rust::keywords::{KEYWORDS, KEYWORDS_MATCH, KEYWORDS_SORTED};
+use global_data_in_rust::lookup::{MatchMap, SortedMap, StrMap};
+use global_data_in_rust::suggest::did_you_mean;
+use global_data_in_rust::tables::{AMMO, AMMO_BY_NAME, WEAPONS, WEAPONS_BY_NAME};
+
+static SORTED: SortedMap<u8> = SortedMap::new(&[("break", 2), ("fn", 3), ("loop", 1)]);
+static MATCH: MatchMap<u8> =
+    MatchMap::new(&[("loop", 1), ("break", 2), ("fn", 3)], |key| match key {
+        "loop" => Some(0),
+        "break" => Some(1),
+        "fn" => Some(2),
+        _ => None,
+    });
+
+#[test]
+fn every_representation_has_the_same_get() {
+    for (key, expected) in [
+        ("loop", Some(&1)),
+        ("fn", Some(&3)),
+        ("loops", None),
+        ("", None),
+    ] {
+        assert_eq!(SORTED.get(key), expected, "{}", key);
+        assert_eq!(MATCH.get(key), expected, "{}", key);
+    }
+    assert!(SORTED.contains_key("break") && MATCH.contains_key("break"));
+    assert_eq!(SORTED.len(), MATCH.len());
+}
+
+#[test]
+fn sorted_maps_iterate_by_key_and_match_maps_in_file_order() {
+    assert_eq!(
+        SORTED.keys().copied().collect::<Vec<_>>(),
+        ["break", "fn", "loop"]
+    );
+    assert_eq!(
+        MATCH.keys().copied().collect::<Vec<_>>(),
+        ["loop", "break", "fn"]
+    );
+    assert_eq!(MATCH.values().sum::<u8>(), 6);
+}
+
+#[test]
+fn generated_indexes_point_at_their_rows() {
+    // `AMMO` is indexed by a sorted array and `WEAPONS` by a phf map, see `build/main.rs`
+    for (name, &i) in AMMO_BY_NAME.entries() {
+        assert_eq!(AMMO[i].name, *name);
+    }
+    for (name, &i) in WEAPONS_BY_NAME.entries() {
+        assert_eq!(WEAPONS[i].name, *name);
+    }
+    assert_eq!(AMMO_BY_NAME.len(), AMMO.len());
+    assert_eq!(did_you_mean(&WEAPONS_BY_NAME, "sward"), ["sword"]);
+    assert_eq!(StrMap::get(&AMMO_BY_NAME, "bolt"), Some(&2));
+}
+
+#[test]
+fn every_representation_of_the_keywords_agrees() {
+    assert_eq!(KEYWORDS_MATCH.len(), KEYWORDS.len());
+    assert_eq!(KEYWORDS_SORTED.len(), KEYWORDS.len());
+    for (keyword, value) in KEYWORDS.entries() {
+        assert_eq!(KEYWORDS_MATCH.get(keyword), Some(value));
+        assert_eq!(KEYWORDS_SORTED.get(keyword), Some(value));
+    }
+    assert_eq!(KEYWORDS_MATCH.get("loops"), None);
+    assert_eq!(KEYWORDS_SORTED.get("loops"), None);
+}
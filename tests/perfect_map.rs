@@ -0,0 +1,62 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use global_data_in_rust::keywords::{self, Keyword2, KEYWORDS, LIVE_KEYWORDS};
+use global_data_in_rust::perfect_map::{DuplicateKey, PerfectMap};
+
+#[test]
+fn runtime_maps_agree_with_generated_ones() {
+    let entries = KEYWORDS
+        .entries()
+        .map(|(k, v)| (k.to_string(), *v))
+        .collect();
+    let map = PerfectMap::new(entries).unwrap();
+    assert_eq!(map.len(), KEYWORDS.len());
+    for word in &["loop", "fn", "extern", "loops", "", "Loop"] {
+        assert_eq!(map.get(*word), KEYWORDS.get(*word), "{}", word);
+    }
+
+    let squares = PerfectMap::new((0..1000u32).map(|i| (i, i * i)).collect()).unwrap();
+    assert_eq!(squares.get(&12), Some(&144));
+    assert_eq!(squares.get(&1000), None);
+}
+
+#[test]
+fn duplicate_keys_are_rejected() {
+    let result = PerfectMap::new(vec![("a", 1), ("b", 2), ("a", 3)]);
+    assert_eq!(result.unwrap_err(), DuplicateKey("a"));
+
+    let empty: PerfectMap<&str, u8> = PerfectMap::new(Vec::new()).unwrap();
+    assert_eq!(empty.get("a"), None);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn reloads_are_seen_whole_by_concurrent_readers() {
+    let done = AtomicBool::new(false);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while !done.load(Ordering::SeqCst) {
+                    // Each table has exactly one of these spellings
+                    let table = LIVE_KEYWORDS.load();
+                    let spellings =
+                        table.contains_key("loop") as u8 + table.contains_key("repeat") as u8;
+                    assert_eq!(spellings, 1);
+                }
+            });
+        }
+        for i in 0..100 {
+            let keyword = if i % 2 == 0 { "repeat" } else { "loop" };
+            keywords::reload(&format!("keyword,variant\n{},Loop\nfn,Fn\n", keyword)).unwrap();
+        }
+        done.store(true, Ordering::SeqCst);
+    });
+
+    // A bad table is reported with its position and leaves the previous one in place
+    let error = keywords::reload("keyword,variant\nloop,Loop\nwhile,While\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, Some(2)));
+    let error = keywords::reload("keyword,variant\nfn,Fn\nfn,Fn\n").unwrap_err();
+    assert_eq!(error.line, 3);
+    assert_eq!(LIVE_KEYWORDS.load().get("loop"), Some(&Keyword2::Loop));
+}
@@ -0,0 +1,62 @@
+#![cfg(unix)]
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use global_data_in_rust::global::{GlobalData, Reloadable};
+use global_data_in_rust::sighup::reload_on_sighup;
+use signal_hook::consts::SIGHUP;
+use signal_hook::low_level::raise;
+
+mod common;
+
+use common::{leak_config, TempFile};
+
+/// Sends SIGHUP until `done` returns true. The tests run in parallel, so a signal meant for one
+/// of them can make the others reload early.
+fn raise_until(mut done: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !done() {
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        raise(SIGHUP).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn sighup_reloads_a_global() {
+    static SOURCE: AtomicU32 = AtomicU32::new(1);
+    static GLOBAL: Reloadable<u32> = Reloadable::new(|| Ok(SOURCE.load(Ordering::SeqCst)));
+
+    let reload = reload_on_sighup(&GLOBAL).unwrap();
+    assert_eq!(*GLOBAL.read(), 1);
+    assert_eq!(reload.status().last_reload, None);
+
+    SOURCE.store(2, Ordering::SeqCst);
+    raise_until(|| *GLOBAL.read() == 2);
+    let status = reload.status();
+    assert!(status.reloads >= 1 && status.last_reload.is_some());
+    assert!(status.error.is_none());
+}
+
+#[test]
+fn failed_reloads_are_reported_in_the_status() {
+    let file = TempFile::new("sighup", "8");
+    let config = leak_config::<u32>(&file, |text| Ok(text.parse()?), |_| Ok(()));
+
+    let reload = reload_on_sighup(config).unwrap();
+    file.write("eight");
+    raise_until(|| reload.status().error.is_some());
+    let error = reload.status().error.unwrap().to_string();
+    assert!(
+        error.ends_with("invalid digit found in string"),
+        "{}",
+        error
+    );
+    assert_eq!(*config.load_full(), 8);
+
+    file.write("9");
+    raise_until(|| reload.status().error.is_none());
+    assert_eq!(*config.load_full(), 9);
+}
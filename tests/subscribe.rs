@@ -0,0 +1,154 @@
+use std::panic;
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use global_data_in_rust::global::{GlobalData, Reloadable};
+use global_data_in_rust::subscribe::Subscribe;
+
+mod common;
+
+use common::{leak_config, TempFile};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Settings {
+    port: u16,
+    motd: &'static str,
+}
+
+#[test]
+fn callbacks_get_the_old_and_new_values() {
+    static GLOBAL: Reloadable<u32> = Reloadable::new(|| Ok(1));
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let id = GLOBAL.subscribe({
+        let seen = seen.clone();
+        move |old, new| seen.lock().unwrap().push((*old, *new))
+    });
+
+    // Loading the first value doesn't replace anything
+    assert_eq!(*GLOBAL.read(), 1);
+    GLOBAL.try_set(2).unwrap();
+    GLOBAL.try_set(3).unwrap();
+    assert!(GLOBAL.unsubscribe(id));
+    GLOBAL.try_set(4).unwrap();
+    assert_eq!(*seen.lock().unwrap(), [(1, 2), (2, 3)]);
+    assert!(!GLOBAL.unsubscribe(id));
+}
+
+#[test]
+fn field_subscriptions_ignore_other_fields() {
+    static GLOBAL: Reloadable<Settings> = Reloadable::new(|| {
+        Ok(Settings {
+            port: 80,
+            motd: "hello",
+        })
+    });
+    let ports = Arc::new(Mutex::new(Vec::new()));
+    GLOBAL.subscribe_field(|settings| settings.port, {
+        let ports = ports.clone();
+        move |old, new| ports.lock().unwrap().push((*old, *new))
+    });
+
+    let settings = GLOBAL.get();
+    GLOBAL
+        .try_set(Settings {
+            motd: "welcome",
+            ..settings.clone()
+        })
+        .unwrap();
+    GLOBAL
+        .try_set(Settings {
+            port: 8080,
+            ..settings
+        })
+        .unwrap();
+    assert_eq!(*ports.lock().unwrap(), [(80, 8080)]);
+}
+
+#[test]
+fn changes_can_be_iterated_on_another_thread() {
+    static GLOBAL: Reloadable<u32> = Reloadable::new(|| Ok(0));
+    GLOBAL.read();
+    let changes = GLOBAL.changes();
+    let listener = thread::spawn(move || {
+        changes
+            .take(3)
+            .map(|change| (*change.old, *change.new))
+            .collect::<Vec<_>>()
+    });
+    for i in 1..=3 {
+        GLOBAL.try_set(i).unwrap();
+    }
+    assert_eq!(listener.join().unwrap(), [(0, 1), (1, 2), (2, 3)]);
+
+    // A new receiver only gets the changes after it was created
+    let changes = GLOBAL.changes();
+    GLOBAL.try_set(4).unwrap();
+    assert_eq!(*changes.try_next().unwrap().new, 4);
+    assert!(changes.try_next().is_none());
+}
+
+#[test]
+fn concurrent_changes_arrive_in_the_order_they_were_published() {
+    static GLOBAL: Reloadable<u32> = Reloadable::new(|| Ok(0));
+    GLOBAL.read();
+    let changes = GLOBAL.changes();
+    let start = Arc::new(Barrier::new(8));
+    let writers: Vec<_> = (1..=8)
+        .map(|thread| {
+            let start = start.clone();
+            thread::spawn(move || {
+                start.wait();
+                for i in 0..1000 {
+                    GLOBAL.try_set(thread * 1000 + i).unwrap();
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let mut previous = 0;
+    for _ in 0..8000 {
+        let change = changes.try_next().unwrap();
+        assert_eq!(*change.old, previous);
+        previous = *change.new;
+    }
+    assert_eq!(previous, *GLOBAL.read());
+}
+
+#[test]
+fn config_changes_come_from_the_watcher() {
+    let file = TempFile::new("subscribe", "8");
+    let config = leak_config::<u32>(&file, |text| Ok(text.parse()?), |_| Ok(()));
+    let changes = config.changes();
+    let _watcher = config.watch(Duration::from_millis(5), |e| panic!("{}", e));
+
+    file.write("16");
+    let change = changes.next_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!((*change.old, *change.new), (8, 16));
+}
+
+#[test]
+fn a_callback_that_publishes_to_its_own_global_panics_instead_of_deadlocking() {
+    static GLOBAL: Reloadable<u32> = Reloadable::new(|| Ok(0));
+    GLOBAL.read();
+    GLOBAL.subscribe(|_, new| {
+        if *new > 100 {
+            GLOBAL.try_set(100).unwrap();
+        }
+    });
+
+    let panic = panic::catch_unwind(|| GLOBAL.try_set(500)).unwrap_err();
+    let message = panic.downcast_ref::<&str>().unwrap();
+    assert!(
+        message.starts_with("a subscriber callback can't publish"),
+        "{}",
+        message
+    );
+
+    // The locks were released along the way
+    GLOBAL.try_set(1).unwrap();
+    assert_eq!(*GLOBAL.read(), 1);
+}
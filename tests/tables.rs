@@ -0,0 +1,28 @@
+use global_data_in_rust::tables::{Ammo, Weapon, AMMO, WEAPONS, WEAPONS_BY_NAME};
+
+#[test]
+fn weapons_are_indexed_by_name() {
+    assert_eq!(WEAPONS_BY_NAME.len(), WEAPONS.len());
+    for weapon in WEAPONS.iter() {
+        assert_eq!(Weapon::get(weapon.name), Some(weapon));
+    }
+    assert_eq!(Weapon::get("banana"), None);
+}
+
+#[test]
+fn fields_have_their_declared_types() {
+    let longbow = Weapon::get("longbow").unwrap();
+    assert_eq!(longbow.damage, 9u16);
+    assert_eq!(longbow.range, 60.0f32);
+    assert!(longbow.two_handed);
+}
+
+#[test]
+fn references_resolve_to_rows_of_the_other_table() {
+    let crossbow = Weapon::get("crossbow").unwrap();
+    let bolt = crossbow.ammo.unwrap();
+    assert_eq!(bolt, Ammo::id("bolt").unwrap());
+    assert_eq!(bolt.get().name, "bolt");
+    assert_eq!(&AMMO[bolt.index()], bolt.get());
+    assert_eq!(Weapon::get("sword").unwrap().ammo, None);
+}
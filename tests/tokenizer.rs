@@ -0,0 +1,52 @@
+use global_data_in_rust::keywords::Keyword2;
+use global_data_in_rust::tokenizer::{tokenize, TokenKind};
+
+#[test]
+fn splits_keywords_identifiers_numbers_and_punctuation() {
+    let source = "fn main() { loop { x += 1.5; } }";
+    let tokens: Vec<(TokenKind, &str)> = tokenize(source)
+        .map(|token| (token.kind, token.text(source)))
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::Keyword(Keyword2::Fn), "fn"),
+            (TokenKind::Identifier, "main"),
+            (TokenKind::Punct('('), "("),
+            (TokenKind::Punct(')'), ")"),
+            (TokenKind::Punct('{'), "{"),
+            (TokenKind::Keyword(Keyword2::Loop), "loop"),
+            (TokenKind::Punct('{'), "{"),
+            (TokenKind::Identifier, "x"),
+            (TokenKind::Punct('+'), "+"),
+            (TokenKind::Punct('='), "="),
+            (TokenKind::Number, "1.5"),
+            (TokenKind::Punct(';'), ";"),
+            (TokenKind::Punct('}'), "}"),
+            (TokenKind::Punct('}'), "}"),
+        ]
+    );
+}
+
+#[test]
+fn keywords_must_be_whole_words() {
+    let kinds: Vec<TokenKind> = tokenize("loops break_ 2.x").map(|token| token.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Identifier,
+            TokenKind::Identifier,
+            TokenKind::Number,
+            TokenKind::Punct('.'),
+            TokenKind::Identifier,
+        ]
+    );
+}
+
+#[test]
+fn spans_are_byte_offsets() {
+    let spans: Vec<(usize, usize)> = tokenize("héllo  42")
+        .map(|token| (token.span.start, token.span.end))
+        .collect();
+    assert_eq!(spans, vec![(0, 6), (8, 10)]);
+}